@@ -0,0 +1,54 @@
+// Times one word attacking one word through the general battle and through the single word fast path that resolve_attack takes.
+// Run with `cargo bench --bench battle`.
+use std::hint::black_box;
+use std::time::Instant;
+
+use truncate::judge::{Judge, Outcome};
+
+const BATTLES: usize = 1_000_000;
+
+fn time_battles(battle: impl Fn(&str, &str) -> bool, pairs: &[(String, String)]) -> f64 {
+    let started = Instant::now();
+    for (attacker, defender) in pairs.iter().cycle().take(BATTLES) {
+        black_box(battle(black_box(attacker), black_box(defender)));
+    }
+    started.elapsed().as_nanos() as f64 / BATTLES as f64
+}
+
+fn main() {
+    let judge = Judge::from_file("./dictionary.txt").expect("Run from the repository root");
+    let words = [
+        "BIG", "FAT", "JOLLY", "SILLY", "FOLK", "ARTS", "XYZ", "QWERTY",
+    ];
+    let pairs: Vec<(String, String)> = words
+        .iter()
+        .flat_map(|attacker| {
+            words
+                .iter()
+                .map(move |defender| (attacker.to_string(), defender.to_string()))
+        })
+        .collect();
+
+    // Both paths have to agree before their timings mean anything
+    for (attacker, defender) in &pairs {
+        assert_eq!(
+            judge.battle_single(attacker, defender),
+            judge.battle(vec![attacker.clone()], vec![defender.clone()])
+        );
+    }
+
+    let general = time_battles(
+        |attacker, defender| {
+            judge.battle(vec![attacker.to_string()], vec![defender.to_string()])
+                == Outcome::DefenderWins
+        },
+        &pairs,
+    );
+    let single = time_battles(
+        |attacker, defender| judge.battle_single(attacker, defender) == Outcome::DefenderWins,
+        &pairs,
+    );
+    println!("{} pairs of words, ns per battle", pairs.len());
+    println!("general battle  {general:>8.1}");
+    println!("single word     {single:>8.1}");
+}
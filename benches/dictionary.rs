@@ -0,0 +1,58 @@
+// Times lookups in each dictionary backend by word length, to show that they grow with the length of the word rather than the size of the dictionary.
+// Run with `cargo bench --features fst`.
+use std::collections::HashSet;
+use std::hint::black_box;
+use std::time::Instant;
+
+use truncate::dictionary::{Dictionary, FstDictionary};
+
+const LOOKUPS: usize = 200_000;
+
+fn time_lookups<D: Dictionary>(
+    dictionary: &D,
+    words: &[String],
+    lookup: fn(&D, &str) -> bool,
+) -> f64 {
+    let started = Instant::now();
+    for word in words.iter().cycle().take(LOOKUPS) {
+        black_box(lookup(dictionary, black_box(word)));
+    }
+    started.elapsed().as_nanos() as f64 / LOOKUPS as f64
+}
+
+fn main() {
+    let words: Vec<String> = std::fs::read_to_string("./dictionary.txt")
+        .expect("Run from the repository root")
+        .lines()
+        .map(|word| word.to_uppercase())
+        .collect();
+    let letters: usize = words.iter().map(|word| word.len()).sum();
+    let hash_set: HashSet<String> = words.iter().cloned().collect();
+    let fst: FstDictionary = words.iter().cloned().collect();
+
+    println!(
+        "{} words, {} letters, {} bytes as an fst",
+        words.len(),
+        letters,
+        fst.size_in_bytes()
+    );
+    println!("length  hash set contains  fst contains  fst has_prefix  (ns per lookup)");
+    for length in 2..=15 {
+        let sample: Vec<String> = words
+            .iter()
+            .filter(|word| word.len() == length)
+            .take(1000)
+            .cloned()
+            .collect();
+        if sample.is_empty() {
+            continue;
+        }
+        println!(
+            "{:>6}  {:>17.1}  {:>12.1}  {:>14.1}",
+            length,
+            time_lookups(&hash_set, &sample, |d, w| d.contains(w)),
+            time_lookups(&fst, &sample, |d, w| d.contains(w)),
+            time_lookups(&fst, &sample, |d, w| d.has_prefix(w)),
+        );
+    }
+}
@@ -0,0 +1,168 @@
+use std::time::{Duration, Instant};
+
+use super::board::Board;
+use super::hand::Hands;
+use super::judge::Judge;
+use super::moves::Move;
+
+// Scores for positions that are already decided, well outside the range of any material count
+const WIN: i32 = 1_000_000;
+const LOSS: i32 = -WIN;
+
+// Searches deeper and deeper until the time runs out, and plays the best move from the deepest search that finished.
+// Falls back to the first legal move if not even a single move deep could be searched in time.
+pub fn search_timed(
+    board: &Board,
+    hands: &Hands,
+    judge: &Judge,
+    player: usize,
+    budget: Duration,
+) -> Option<Move> {
+    let deadline = Instant::now() + budget;
+    let moves = board.legal_moves(player, hands);
+    let mut best = moves.first()?.clone();
+
+    for depth in 1.. {
+        match search_root(board, hands, judge, player, &moves, depth, deadline) {
+            Some((found, score)) => {
+                best = found;
+                if score == WIN || score == LOSS {
+                    break; // Searching deeper can't change a forced result
+                }
+            }
+            None => break,
+        }
+    }
+    Some(best)
+}
+
+// The best move and its score at the given depth, or None if the deadline passed first
+fn search_root(
+    board: &Board,
+    hands: &Hands,
+    judge: &Judge,
+    player: usize,
+    moves: &[Move],
+    depth: usize,
+    deadline: Instant,
+) -> Option<(Move, i32)> {
+    let mut best: Option<(Move, i32)> = None;
+    for candidate in moves {
+        let mut board = board.clone();
+        let mut hands = hands.clone();
+        if board
+            .make_move(candidate.clone(), &mut hands, judge)
+            .is_err()
+        {
+            continue;
+        }
+        let opponent = next_player(&board, player);
+        let score = -negamax(&board, &hands, judge, opponent, depth - 1, deadline)?;
+        if best
+            .as_ref()
+            .is_none_or(|(_, best_score)| score > *best_score)
+        {
+            best = Some((candidate.clone(), score));
+        }
+    }
+    best
+}
+
+// The value of the position to the player about to move, assuming both sides play their best from here
+fn negamax(
+    board: &Board,
+    hands: &Hands,
+    judge: &Judge,
+    player: usize,
+    depth: usize,
+    deadline: Instant,
+) -> Option<i32> {
+    if Instant::now() >= deadline {
+        return None;
+    }
+    if let Some(winner) = Judge::winner(board) {
+        return Some(if winner == player { WIN } else { LOSS });
+    }
+    if depth == 0 {
+        return Some(material(board, player));
+    }
+
+    let mut best = None;
+    for candidate in board.legal_moves(player, hands) {
+        let mut board = board.clone();
+        let mut hands = hands.clone();
+        if board.make_move(candidate, &mut hands, judge).is_err() {
+            continue;
+        }
+        let opponent = next_player(&board, player);
+        let score = -negamax(&board, &hands, judge, opponent, depth - 1, deadline)?;
+        best = best.max(Some(score));
+    }
+    Some(best.unwrap_or_else(|| material(board, player)))
+}
+
+// How many more tiles the player has on the board than everyone else put together
+fn material(board: &Board, player: usize) -> i32 {
+    (0..board.get_orientations().len())
+        .map(|owner| {
+            let tiles = board.tiles_of(owner).len() as i32;
+            if owner == player {
+                tiles
+            } else {
+                -tiles
+            }
+        })
+        .sum()
+}
+
+fn next_player(board: &Board, player: usize) -> usize {
+    (player + 1) % board.get_orientations().len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::bag::tests as TileUtils;
+    use super::*;
+    use crate::board::{tests as BoardUtils, Coordinate, Direction};
+    use crate::moves::tests::short_dict;
+
+    #[test]
+    fn search_timed() {
+        // Player 0 can win by reaching the fourth row, but the first legal move is back beside their root
+        let b = BoardUtils::from_string(
+            [
+                "_ _ X _ _",
+                "_ _ X _ _",
+                "_ X X _ _",
+                "_ _ _ _ _",
+                "_ _ _ _ B",
+            ]
+            .join("\n"),
+            vec![Coordinate { x: 2, y: 0 }, Coordinate { x: 4, y: 4 }],
+            vec![Direction::North, Direction::South],
+        )
+        .unwrap();
+        let hands = Hands::new(2, 7, TileUtils::trivial_bag());
+        let judge = short_dict();
+
+        // Without any time, the first legal move is still played
+        let started = Instant::now();
+        let hurried = super::search_timed(&b, &hands, &judge, 0, Duration::ZERO).unwrap();
+        assert!(started.elapsed() < Duration::from_secs(1));
+        assert_eq!(b.check_move(&hurried, &hands, &judge), Ok(()));
+        let mut after = b.clone();
+        after
+            .make_move(hurried, &mut hands.clone(), &judge)
+            .unwrap();
+        assert_eq!(Judge::winner(&after), None);
+
+        // With time to look, the winning move is found
+        let considered =
+            super::search_timed(&b, &hands, &judge, 0, Duration::from_secs(5)).unwrap();
+        let mut after = b.clone();
+        after
+            .make_move(considered, &mut hands.clone(), &judge)
+            .unwrap();
+        assert_eq!(Judge::winner(&after), Some(0));
+    }
+}
@@ -1,24 +1,112 @@
-use rand::Rng;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+use std::collections::HashMap;
 use std::fmt;
 
-#[derive(Debug)]
+use crate::error::GamePlayError;
+
+// Blank tiles can be played as any letter. Moves write a blank as the lowercase version of the letter it's played as, following Scrabble's notation.
+pub const BLANK: char = '*';
+
+// The tile that a letter in a move is played from, which is a blank for lowercase letters
+pub fn tile_of(letter: char) -> char {
+    if letter.is_lowercase() {
+        BLANK
+    } else {
+        letter
+    }
+}
+
+// The letter that a tile in a move spells, which for a blank is the letter it's played as
+pub fn letter_of(tile: char) -> char {
+    tile.to_uppercase().next().unwrap_or(tile)
+}
+
+#[derive(Clone, Debug)]
 pub struct TileBag {
     bag: Vec<char>,
-    rng: rand::rngs::ThreadRng,
+    rng: ChaCha12Rng, // Owned rather than thread local, so that a cloned bag draws the same tiles as the original
     letter_distribution: [usize; 26],
+    blanks: usize,
+}
+
+// Where a bag's random number generator is up to, so that a saved game can be resumed with the same draws to come
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RngState {
+    seed: [u8; 32],
+    stream: u64,
+    word_pos: u128,
 }
 
 impl TileBag {
     pub fn new(letter_distribution: [usize; 26]) -> Self {
         let mut tile_bag = TileBag {
             bag: Vec::new(),
-            rng: rand::thread_rng(),
+            rng: ChaCha12Rng::from_entropy(),
             letter_distribution,
+            blanks: 0,
         };
         tile_bag.fill();
         tile_bag
     }
 
+    // A bag holding the given number of each tile, e.g. `[('E', 12), ('Q', 1)]`, where blanks are written as `BLANK`.
+    // Counts for the same tile add up, and anything other than an uppercase letter or a blank is rejected.
+    pub fn from_distribution(distribution: &[(char, usize)]) -> Result<Self, GamePlayError> {
+        let mut letter_distribution = [0; 26];
+        let mut blanks = 0;
+        for &(tile, count) in distribution {
+            match tile {
+                BLANK => blanks += count,
+                'A'..='Z' => letter_distribution[tile as usize - 65] += count,
+                _ => return Err(GamePlayError::InvalidLetter { tile }),
+            }
+        }
+        Ok(Self::new(letter_distribution).with_blanks(blanks))
+    }
+
+    // The 100 tiles of a standard English word game, including two blanks
+    pub fn standard_english() -> Self {
+        Self::from_distribution(&[
+            ('A', 9),
+            ('B', 2),
+            ('C', 2),
+            ('D', 4),
+            ('E', 12),
+            ('F', 2),
+            ('G', 3),
+            ('H', 2),
+            ('I', 9),
+            ('J', 1),
+            ('K', 1),
+            ('L', 4),
+            ('M', 2),
+            ('N', 6),
+            ('O', 8),
+            ('P', 2),
+            ('Q', 1),
+            ('R', 6),
+            ('S', 4),
+            ('T', 6),
+            ('U', 4),
+            ('V', 2),
+            ('W', 2),
+            ('X', 1),
+            ('Y', 2),
+            ('Z', 1),
+            (BLANK, 2),
+        ])
+        .expect("The standard distribution only has letters and blanks")
+    }
+
+    // Adds blank tiles to the bag, and to every refill after it
+    pub fn with_blanks(mut self, blanks: usize) -> Self {
+        self.blanks = blanks;
+        self.bag.extend([BLANK].repeat(blanks));
+        self
+    }
+
     pub fn draw_tile(&mut self) -> char {
         if self.bag.is_empty() {
             self.fill();
@@ -27,11 +115,97 @@ impl TileBag {
         self.bag.swap_remove(index)
     }
 
+    // The number of tiles left before the bag refills
+    pub fn len(&self) -> usize {
+        self.bag.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bag.is_empty()
+    }
+
+    // The number of tiles in the bag each time it is refilled
+    pub fn refill_size(&self) -> usize {
+        self.letter_distribution.iter().sum::<usize>() + self.blanks
+    }
+
     // TODO: this doesn't stop us from returning tiles that weren't originally in the bag
     pub fn return_tile(&mut self, c: char) {
         self.bag.push(c);
     }
 
+    // How many of each letter are left before the bag refills
+    pub fn remaining_counts(&self) -> HashMap<char, usize> {
+        let mut counts = HashMap::new();
+        for &tile in &self.bag {
+            *counts.entry(tile).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    // The chance that the next tile drawn is the letter, which comes from a fresh bag if this one is empty
+    pub fn probability_of(&self, letter: char) -> f64 {
+        if self.bag.is_empty() {
+            return match self.refill_size() {
+                0 => 0.0,
+                size => self.refill_count(letter) as f64 / size as f64,
+            };
+        }
+        let count = self.remaining_counts().get(&letter).copied().unwrap_or(0);
+        count as f64 / self.bag.len() as f64
+    }
+
+    // The average number of tiles drawn up to and including the first copy of the letter, counting any refills along the way.
+    // Infinite if the letter is never in the bag.
+    pub fn expected_draws_until(&self, letter: char) -> f64 {
+        // The first of k copies among n tiles drawn without replacement comes, on average, at draw (n + 1) / (k + 1)
+        let first_copy = |copies: usize, tiles: usize| (tiles + 1) as f64 / (copies + 1) as f64;
+
+        let remaining = self.remaining_counts().get(&letter).copied().unwrap_or(0);
+        if remaining > 0 {
+            return first_copy(remaining, self.bag.len());
+        }
+        match self.refill_count(letter) {
+            0 => f64::INFINITY,
+            copies => self.bag.len() as f64 + first_copy(copies, self.refill_size()),
+        }
+    }
+
+    fn refill_count(&self, letter: char) -> usize {
+        if letter == BLANK {
+            return self.blanks;
+        }
+        match (letter as usize).checked_sub(65) {
+            Some(index) if index < 26 => self.letter_distribution[index],
+            _ => 0,
+        }
+    }
+
+    // Draws the same tiles in the same order as every other bag with the same seed and contents, e.g. for replays and tests
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.reseed(seed);
+        self
+    }
+
+    pub fn reseed(&mut self, seed: u64) {
+        self.rng = ChaCha12Rng::seed_from_u64(seed);
+    }
+
+    pub fn rng_state(&self) -> RngState {
+        RngState {
+            seed: self.rng.get_seed(),
+            stream: self.rng.get_stream(),
+            word_pos: self.rng.get_word_pos(),
+        }
+    }
+
+    // Only restores the random number generator, so the bag's tiles must already match the saved game's
+    pub fn set_rng_state(&mut self, state: RngState) {
+        self.rng = ChaCha12Rng::from_seed(state.seed);
+        self.rng.set_stream(state.stream);
+        self.rng.set_word_pos(state.word_pos);
+    }
+
     fn fill(&mut self) {
         self.bag.extend(
             self.letter_distribution
@@ -39,6 +213,7 @@ impl TileBag {
                 .enumerate()
                 .flat_map(|(letter, count)| [((letter as u8) + 65) as char].repeat(*count)),
         );
+        self.bag.extend([BLANK].repeat(self.blanks));
     }
 }
 
@@ -53,10 +228,14 @@ impl Default for TileBag {
 
 impl PartialEq for TileBag {
     fn eq(&self, rhs: &Self) -> bool {
-        self.bag == rhs.bag && self.letter_distribution == rhs.letter_distribution
+        self.bag == rhs.bag
+            && self.letter_distribution == rhs.letter_distribution
+            && self.blanks == rhs.blanks
     }
 }
 
+impl Eq for TileBag {}
+
 impl fmt::Display for TileBag {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "Letters in the bag:\n{:?}", self.bag)
@@ -75,6 +254,95 @@ pub mod tests {
         assert_eq!(drawn.filter(|&x| x == 'A').count(), 5);
     }
 
+    #[test]
+    fn draw_probabilities() {
+        let mut dist = [0; 26];
+        dist[0] = 3;
+        dist[1] = 1;
+        let mut bag = TileBag::new(dist);
+        assert_eq!(bag.remaining_counts(), HashMap::from([('A', 3), ('B', 1)]));
+        assert_eq!(bag.probability_of('A'), 0.75);
+        assert_eq!(bag.probability_of('B'), 0.25);
+        assert_eq!(bag.probability_of('C'), 0.0);
+        assert_eq!(bag.expected_draws_until('A'), 1.25);
+        assert_eq!(bag.expected_draws_until('B'), 2.5);
+        assert_eq!(bag.expected_draws_until('C'), f64::INFINITY);
+
+        // An empty bag's next draw comes from a refill
+        for _ in 0..4 {
+            bag.draw_tile();
+        }
+        assert!(bag.is_empty());
+        assert_eq!(bag.probability_of('A'), 0.75);
+        assert_eq!(bag.expected_draws_until('B'), 2.5);
+
+        // Without a B left, the rest of the bag has to be drawn before a fresh B can come
+        bag.return_tile('A');
+        bag.return_tile('A');
+        assert_eq!(bag.probability_of('A'), 1.0);
+        assert_eq!(bag.probability_of('B'), 0.0);
+        assert_eq!(bag.expected_draws_until('A'), 1.0);
+        assert_eq!(bag.expected_draws_until('B'), 4.5);
+    }
+
+    #[test]
+    fn from_distribution() {
+        let mut bag = TileBag::from_distribution(&[('E', 2), ('Q', 1), ('E', 1), (BLANK, 1)])
+            .unwrap()
+            .with_seed(3);
+        assert_eq!(bag.len(), 5);
+        assert_eq!(bag.remaining_counts()[&'E'], 3);
+
+        // Draws use up exactly the tiles in the distribution
+        let mut drawn: Vec<char> = (0..5).map(|_| bag.draw_tile()).collect();
+        drawn.sort();
+        assert_eq!(drawn, vec![BLANK, 'E', 'E', 'E', 'Q']);
+        assert!(bag.is_empty());
+
+        // Returned tiles can be drawn again
+        bag.return_tile('Q');
+        assert_eq!(bag.draw_tile(), 'Q');
+
+        assert_eq!(
+            TileBag::from_distribution(&[('e', 1)]),
+            Err(GamePlayError::InvalidLetter { tile: 'e' })
+        );
+    }
+
+    #[test]
+    fn standard_english() {
+        let bag = TileBag::standard_english();
+        assert_eq!(bag.len(), 100);
+        assert_eq!(bag.refill_size(), 100);
+        assert_eq!(bag.remaining_counts()[&'E'], 12);
+        assert_eq!(bag.remaining_counts()[&BLANK], 2);
+    }
+
+    #[test]
+    fn restores_rng_state() {
+        let mut bag = TileBag::default();
+        for _ in 0..bag.refill_size() {
+            bag.draw_tile();
+        }
+        // Save the bag mid game, just as it's about to refill
+        let state = bag.rng_state();
+        let upcoming: Vec<char> = (0..50).map(|_| bag.draw_tile()).collect();
+
+        let mut restored = TileBag::default();
+        restored.set_rng_state(state);
+        assert_eq!(restored.rng_state(), state);
+        let redrawn: Vec<char> = (0..50).map(|_| restored.draw_tile()).collect();
+        assert_eq!(redrawn, upcoming);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serializes_rng_state() {
+        let state = TileBag::default().rng_state();
+        let json = serde_json::to_string(&state).unwrap();
+        assert_eq!(serde_json::from_str::<RngState>(&json).unwrap(), state);
+    }
+
     // Util functions
     pub fn a_b_bag() -> TileBag {
         let mut dist = [0; 26];
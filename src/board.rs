@@ -1,13 +1,18 @@
 use crate::error::GamePlayError;
 use anyhow::Result;
-use std::collections::HashSet;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 use std::fmt;
+use std::hash::{Hash, Hasher};
 use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
 
+use super::bag::{letter_of, tile_of, BLANK};
 use super::hand::Hands;
+use super::rules::Rules;
 
-#[derive(EnumIter, Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(EnumIter, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Direction {
     South,
     East,
@@ -36,11 +41,77 @@ impl Direction {
     }
 }
 
-#[derive(PartialEq, Eq, Debug)]
+// What happens at each side of the board. By default each player's goal is the side opposite them, and the other sides are walls.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EdgeKind {
+    Goal(usize), // The player wins by reaching this side
+    Wall,
+    Wrap, // Squares on this side neighbour the squares on the other side of the grid
+}
+
+// Boards are cloned freely by search code, so all of their state must be owned rather than shared
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(from = "SerializedBoard")
+)]
+#[derive(Clone, PartialEq, Eq, Debug)]
 pub struct Board {
     squares: Vec<Vec<Option<Square>>>,
     roots: Vec<Coordinate>,
     orientations: Vec<Direction>, // The side of the board that the player is sitting at, and the direction that their vertical words go in
+    rules: Rules,
+    edges: BTreeMap<Direction, EdgeKind>, // Sides that don't follow the default goals and walls, ordered so boards serialize and hash the same way every time
+    changes: Option<ChangeLayer>, // Only kept when asked for, e.g. by a UI that highlights recent moves
+    #[cfg_attr(feature = "serde", serde(skip))]
+    zobrist: u64, // Kept up to date as squares change, so that search code can hash positions cheaply
+}
+
+// A board as it's serialized, i.e. without its hash, which is recomputed from the squares when the board is read back.
+// That way a board that was edited, or written by another program, can't carry a hash that disagrees with its squares.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct SerializedBoard {
+    squares: Vec<Vec<Option<Square>>>,
+    roots: Vec<Coordinate>,
+    orientations: Vec<Direction>,
+    rules: Rules,
+    edges: BTreeMap<Direction, EdgeKind>,
+    changes: Option<ChangeLayer>,
+}
+
+#[cfg(feature = "serde")]
+impl From<SerializedBoard> for Board {
+    fn from(serialized: SerializedBoard) -> Self {
+        let mut board = Board {
+            squares: serialized.squares,
+            roots: serialized.roots,
+            orientations: serialized.orientations,
+            rules: serialized.rules,
+            edges: serialized.edges,
+            changes: serialized.changes,
+            zobrist: 0,
+        };
+        board.zobrist = board.recompute_zobrist();
+        board
+    }
+}
+
+// The last move that changed a square, whether by placing, swapping, capturing or truncating
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Change {
+    pub turn: usize,
+    pub player: usize, // The player who made the move
+}
+
+// Stamps laid over the squares, one per square, along with the number of moves made so far
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct ChangeLayer {
+    turn: usize,
+    stamps: Vec<Vec<Option<Change>>>,
 }
 
 impl Board {
@@ -67,6 +138,272 @@ impl Board {
             squares,
             roots,
             orientations: vec![Direction::North, Direction::South],
+            rules: Rules::default(),
+            edges: BTreeMap::new(),
+            changes: None,
+            zobrist: 0, // Empty squares don't contribute to the hash
+        }
+    }
+
+    // A default board with some tiles already placed, e.g. for handicaps or puzzle scenarios.
+    // Each pre-placed tile must be connected to its owner's root through their other tiles.
+    pub fn with_initial_tiles(
+        width: usize,
+        height: usize,
+        tiles: Vec<(Coordinate, usize, char)>,
+    ) -> Result<Self, GamePlayError> {
+        let mut board = Self::new(width, height);
+        for &(position, player, tile) in &tiles {
+            if let Square::Occupied(..) = board.get(position)? {
+                return Err(GamePlayError::OccupiedPlace);
+            }
+            board.set(position, player, tile)?;
+        }
+
+        for &(position, player, _) in &tiles {
+            if !board
+                .depth_first_search(board.get_root(player)?)
+                .contains(&position)
+            {
+                return Err(GamePlayError::NonAdjacentPlace);
+            }
+        }
+
+        Ok(board)
+    }
+
+    // A two player board where player 1's root is player 0's root reflected through the centre, so neither player starts closer to the middle
+    pub fn symmetric(width: usize, height: usize) -> Self {
+        let mut board = Board::new(width, height);
+        let root = board.roots[0];
+        let mirrored = Coordinate {
+            x: board.width() - 1 - root.x,
+            y: board.height() - 1 - root.y,
+        };
+        board.squares[board.roots[1].y][board.roots[1].x] = None;
+        board.squares[mirrored.y][mirrored.x] = Some(Square::Empty);
+        board.roots[1] = mirrored;
+        board.orientations = vec![Direction::North, Direction::North.opposite()];
+        board
+            .validate()
+            .expect("An empty board with roots on the home rows is valid");
+        board
+    }
+
+    // Checks that the board is internally consistent, e.g. after being constructed by hand or deserialized
+    pub fn validate(&self) -> Result<(), GamePlayError> {
+        let attached = self
+            .roots
+            .iter()
+            .map(|&root| {
+                self.get(root)?;
+                Ok(self.depth_first_search(root))
+            })
+            .collect::<Result<Vec<HashSet<Coordinate>>, GamePlayError>>()?;
+
+        for y in 0..self.height() {
+            for x in 0..self.width() {
+                let position = Coordinate { x, y };
+                if let Ok(Square::Occupied(player, _, _)) = self.get(position) {
+                    match attached.get(player) {
+                        None => return Err(GamePlayError::NonExistentPlayer { index: player }),
+                        Some(tiles) if !tiles.contains(&position) => {
+                            return Err(GamePlayError::DisconnectedTile { position })
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+
+        if self.zobrist != self.recompute_zobrist() {
+            return Err(GamePlayError::StaleHash);
+        }
+
+        Ok(())
+    }
+
+    // Identifies the position, i.e. the squares, roots, orientations and edges but not the rules, so that repeated positions can be spotted
+    pub fn canonical_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.squares.hash(&mut hasher);
+        self.roots.hash(&mut hasher);
+        self.orientations.hash(&mut hasher);
+        for side in Direction::iter() {
+            self.edge(side).hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+
+    // A Zobrist hash of the tiles on the board, i.e. each tile's position, owner and letter, but not the roots, orientations or rules.
+    // It's updated as squares change rather than recomputed, so it's cheap enough to call at every node of a search.
+    pub fn zobrist_hash(&self) -> u64 {
+        self.zobrist
+    }
+
+    fn recompute_zobrist(&self) -> u64 {
+        let mut hash = 0;
+        for (y, row) in self.squares.iter().enumerate() {
+            for (x, square) in row.iter().enumerate() {
+                if let Some(square) = square {
+                    hash ^= zobrist_key(Coordinate { x, y }, *square);
+                }
+            }
+        }
+        hash
+    }
+
+    pub fn with_rules(mut self, rules: Rules) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    pub fn rules(&self) -> &Rules {
+        &self.rules
+    }
+
+    // Overrides what happens at one side of the board, e.g. to move a player's goal or to let play wrap around.
+    // Wrapping joins a side to the side opposite it, so it always applies to both, and replacing either side of a wrap undoes it on the other.
+    pub fn with_edge(mut self, side: Direction, kind: EdgeKind) -> Self {
+        if kind == EdgeKind::Wrap {
+            self.edges.insert(side.opposite(), kind);
+        } else if self.edge(side) == EdgeKind::Wrap {
+            self.edges.remove(&side.opposite());
+        }
+        self.edges.insert(side, kind);
+        self
+    }
+
+    pub fn edge(&self, side: Direction) -> EdgeKind {
+        if let Some(kind) = self.edges.get(&side) {
+            return *kind;
+        }
+        match self
+            .orientations
+            .iter()
+            .position(|orientation| orientation.opposite() == side)
+        {
+            Some(player) => EdgeKind::Goal(player),
+            None => EdgeKind::Wall,
+        }
+    }
+
+    // The sides of the board the player wins by reaching
+    pub fn goal_edges(&self, player: usize) -> Vec<Direction> {
+        Direction::iter()
+            .filter(|&side| self.edge(side) == EdgeKind::Goal(player))
+            .collect()
+    }
+
+    // Starts stamping every square a move changes, with the turn it was changed on and who by
+    pub fn with_change_tracking(mut self) -> Self {
+        self.changes = Some(ChangeLayer {
+            turn: 0,
+            stamps: vec![vec![None; self.width()]; self.height()],
+        });
+        self
+    }
+
+    pub fn tracks_changes(&self) -> bool {
+        self.changes.is_some()
+    }
+
+    // The number of moves made since change tracking started
+    pub fn turn(&self) -> Option<usize> {
+        self.changes.as_ref().map(|changes| changes.turn)
+    }
+
+    pub fn change_at(&self, position: Coordinate) -> Option<Change> {
+        self.changes
+            .as_ref()?
+            .stamps
+            .get(position.y)?
+            .get(position.x)
+            .copied()
+            .flatten()
+    }
+
+    // Every square changed on or after the given turn, from the top left, or nothing if changes aren't tracked
+    pub fn recently_changed(&self, since_turn: usize) -> Vec<Coordinate> {
+        let mut changed = Vec::new();
+        for y in 0..self.height() {
+            for x in 0..self.width() {
+                let position = Coordinate { x, y };
+                if self
+                    .change_at(position)
+                    .is_some_and(|change| change.turn >= since_turn)
+                {
+                    changed.push(position);
+                }
+            }
+        }
+        changed
+    }
+
+    // Stamps every square that differs from the board before the player's move, and moves on to the next turn
+    pub(crate) fn stamp_changes(&mut self, before: &Board, player: usize) {
+        let changed = self
+            .changed_since(before)
+            .expect("Moves don't change the size of the board");
+        let Some(changes) = self.changes.as_mut() else {
+            return;
+        };
+        for (position, _) in changed {
+            changes.stamps[position.y][position.x] = Some(Change {
+                turn: changes.turn,
+                player,
+            });
+        }
+        changes.turn += 1;
+    }
+
+    // Puts back the stamps from before the last move, and goes back to that move's turn
+    pub(crate) fn unstamp_changes(&mut self, previous: Vec<(Coordinate, Option<Change>)>) {
+        let Some(changes) = self.changes.as_mut() else {
+            return;
+        };
+        for (position, stamp) in previous {
+            changes.stamps[position.y][position.x] = stamp;
+        }
+        changes.turn = changes.turn.saturating_sub(1);
+    }
+
+    // The squares that differ from an earlier version of the board, with what they held back then
+    pub fn changed_since(
+        &self,
+        before: &Board,
+    ) -> Result<Vec<(Coordinate, Square)>, GamePlayError> {
+        if (self.width(), self.height()) != (before.width(), before.height()) {
+            return Err(GamePlayError::MismatchedDimensions {
+                expected: (self.width(), self.height()),
+                found: (before.width(), before.height()),
+            });
+        }
+        let mut changed = Vec::new();
+        for (y, row) in self.squares.iter().enumerate() {
+            for (x, square) in row.iter().enumerate() {
+                if let (Some(old), Some(new)) = (before.squares[y][x], square) {
+                    if old != *new {
+                        changed.push((Coordinate { x, y }, old));
+                    }
+                }
+            }
+        }
+        Ok(changed)
+    }
+
+    // Puts any kind of square back, e.g. when taking back a move
+    pub fn restore(&mut self, position: Coordinate, square: Square) -> Result<(), GamePlayError> {
+        match square {
+            Square::Empty => {
+                self.get(position)?;
+                self.clear(position);
+                Ok(())
+            }
+            Square::Occupied(player, ..) if self.roots.get(player).is_none() => {
+                Err(GamePlayError::NonExistentPlayer { index: player })
+            }
+            Square::Occupied(..) | Square::Neutral(_) => self.replace_square(position, square),
         }
     }
 
@@ -87,6 +424,7 @@ impl Board {
         }
     }
 
+    // Puts the player's tile on the square. As in a move, a lowercase letter is a blank played as that letter.
     pub fn set(
         &mut self,
         position: Coordinate,
@@ -97,13 +435,32 @@ impl Board {
             return Err(GamePlayError::NonExistentPlayer { index: player });
         }
 
+        self.replace_square(
+            position,
+            Square::Occupied(player, letter_of(value), tile_of(value) == BLANK),
+        )
+    }
+
+    // Puts an obstacle on the board that belongs to nobody
+    pub fn set_neutral(&mut self, position: Coordinate, value: char) -> Result<(), GamePlayError> {
+        self.replace_square(position, Square::Neutral(value))
+    }
+
+    // TODO: safety on index access like get and set - ideally combine error checking for all 3
+    pub fn clear(&mut self, position: Coordinate) {
+        let _ = self.replace_square(position, Square::Empty);
+    }
+
+    // Every change to a playable square goes through here, to keep the hash in step with the squares
+    fn replace_square(&mut self, position: Coordinate, value: Square) -> Result<(), GamePlayError> {
         match self
             .squares
             .get_mut(position.y)
             .and_then(|row| row.get_mut(position.x))
         {
             Some(Some(square)) => {
-                *square = Square::Occupied(player, value);
+                self.zobrist ^= zobrist_key(position, *square) ^ zobrist_key(position, value);
+                *square = value;
                 Ok(())
             }
             Some(None) => Err(GamePlayError::InvalidPosition { position }),
@@ -111,34 +468,73 @@ impl Board {
         }
     }
 
-    // TODO: safety on index access like get and set - ideally combine error checking for all 3
-    pub fn clear(&mut self, position: Coordinate) {
-        if let Some(pos) = self
-            .squares
-            .get_mut(position.y as usize)
-            .and_then(|y| y.get_mut(position.x as usize))
-        {
-            *pos = Some(Square::Empty);
-        }
+    // Removes every tile that isn't connected to its owner's root. A root only anchors its own player's tiles,
+    // so a clump that's connected to someone else's root, e.g. through a tile placed on it, is removed too.
+    pub fn truncate(&mut self, hands: &mut Hands) -> Vec<(Coordinate, char)> {
+        let anchors: Vec<Coordinate> = self
+            .roots
+            .iter()
+            .enumerate()
+            .filter(|&(player, &root)| {
+                matches!(self.get(root), Ok(Square::Occupied(owner, _, _)) if owner == player)
+            })
+            .map(|(_, &root)| root)
+            .collect();
+        self.truncate_from(&anchors, hands)
     }
 
-    pub fn truncate(&mut self, hands: &mut Hands) {
+    // Removes every tile that isn't connected to one of the anchors through tiles of the same player, returning the removed tiles to the bag.
+    // Reports which tiles were removed, from the top left.
+    pub fn truncate_from(
+        &mut self,
+        anchors: &[Coordinate],
+        hands: &mut Hands,
+    ) -> Vec<(Coordinate, char)> {
+        let mut removed = Vec::new();
         let mut attatched = HashSet::new();
-        for root in self.roots.iter() {
-            attatched.extend(self.depth_first_search(*root));
+        for anchor in anchors {
+            attatched.extend(self.depth_first_search(*anchor));
         }
 
         for y in 0..self.height() {
             for x in 0..self.width() {
                 let c = Coordinate { x, y };
                 if !attatched.contains(&c) {
-                    if let Ok(Square::Occupied(_, letter)) = self.get(c) {
-                        hands.return_tile(letter);
+                    if let Ok(square @ Square::Occupied(..)) = self.get(c) {
+                        let tile = square.tile().expect("The square holds a tile");
+                        hands.return_tile(tile);
+                        self.clear(c);
+                        removed.push((c, tile));
+                    }
+                }
+            }
+        }
+        removed
+    }
+
+    // All of the squares occupied by the player, along with their letters
+    pub fn tiles_of(&self, player: usize) -> Vec<(Coordinate, char)> {
+        let mut tiles = Vec::new();
+        for y in 0..self.height() {
+            for x in 0..self.width() {
+                let position = Coordinate { x, y };
+                if let Ok(Square::Occupied(owner, letter, _)) = self.get(position) {
+                    if owner == player {
+                        tiles.push((position, letter));
                     }
-                    self.clear(c);
                 }
             }
         }
+        tiles
+    }
+
+    // The player's tiles that aren't part of any word of two or more letters, which hold territory but don't help in battles
+    pub fn orphan_tiles(&self, player: usize) -> Vec<Coordinate> {
+        self.tiles_of(player)
+            .into_iter()
+            .map(|(position, _)| position)
+            .filter(|&position| self.get_words(position).iter().all(|word| word.len() < 2))
+            .collect()
     }
 
     pub fn get_root(&self, player: usize) -> Result<Coordinate, GamePlayError> {
@@ -151,7 +547,13 @@ impl Board {
     pub fn neighbouring_squares(&self, position: Coordinate) -> Vec<(Coordinate, Square)> {
         Direction::iter()
             .filter_map(|delta| {
-                let neighbour_coordinate = position.add(delta);
+                let mut neighbour_coordinate = position.add(delta);
+                if self.edge(delta) == EdgeKind::Wrap && self.at_playable_edge(position, delta) {
+                    neighbour_coordinate = self.wrap(position, delta)?;
+                }
+                if neighbour_coordinate == position {
+                    return None;
+                }
                 if let Ok(square) = self.get(neighbour_coordinate) {
                     Some((neighbour_coordinate, square))
                 } else {
@@ -161,15 +563,57 @@ impl Board {
             .collect()
     }
 
+    // Whether there are no playable squares between the position and the given side of the grid, e.g. only the dead squares of a home row
+    fn at_playable_edge(&self, position: Coordinate, side: Direction) -> bool {
+        let mut beyond = position.add(side);
+        loop {
+            match self.get(beyond) {
+                Ok(_) => return false,
+                Err(GamePlayError::OutSideBoardDimensions { .. }) => return true,
+                Err(_) => beyond = beyond.add(side),
+            }
+        }
+    }
+
+    // The first playable square in the position's row or column, counting in from the side opposite the one it stepped off.
+    // Dead squares, like the home rows either side of the board, are skipped over. None if the whole line is dead.
+    fn wrap(&self, position: Coordinate, side: Direction) -> Option<Coordinate> {
+        let mut square = match side {
+            Direction::North => Coordinate {
+                x: position.x,
+                y: self.height() - 1,
+            },
+            Direction::South => Coordinate {
+                x: position.x,
+                y: 0,
+            },
+            Direction::East => Coordinate {
+                x: 0,
+                y: position.y,
+            },
+            Direction::West => Coordinate {
+                x: self.width() - 1,
+                y: position.y,
+            },
+        };
+        loop {
+            match self.get(square) {
+                Ok(_) => return Some(square),
+                Err(GamePlayError::OutSideBoardDimensions { .. }) => return None,
+                Err(_) => square = square.add(side),
+            }
+        }
+    }
+
     // TODO: return iterator or rename since it doesn't matter that this is depth first when we return a HashSet
     fn depth_first_search(&self, position: Coordinate) -> HashSet<Coordinate> {
         let mut visited = HashSet::new();
 
         fn dfs(b: &Board, position: Coordinate, visited: &mut HashSet<Coordinate>) {
-            if let Ok(Square::Occupied(player, _)) = b.get(position) {
+            if let Ok(Square::Occupied(player, _, _)) = b.get(position) {
                 visited.insert(position);
                 for (position, square) in b.neighbouring_squares(position) {
-                    if let Square::Occupied(neighbours_player, _) = square {
+                    if let Square::Occupied(neighbours_player, _, _) = square {
                         if !visited.contains(&position) && player == neighbours_player {
                             dfs(b, position, visited);
                         };
@@ -182,34 +626,60 @@ impl Board {
         visited
     }
 
+    // Swaps only exchange letters between squares the player already holds, so they can never disconnect a tile from its root
     pub fn swap(&mut self, player: usize, positions: [Coordinate; 2]) -> Result<(), GamePlayError> {
+        for (position, square) in self.preview_swap(player, positions)? {
+            self.replace_square(position, square)?;
+        }
+
+        Ok(())
+    }
+
+    // Takes back a swap by swapping the same squares again, checking that they still hold two tiles of the same player
+    pub fn undo_swap(&mut self, positions: [Coordinate; 2]) -> Result<(), GamePlayError> {
+        match self.get(positions[0])? {
+            Square::Empty => Err(GamePlayError::UnoccupiedSwap),
+            Square::Neutral(_) => Err(GamePlayError::UnownedSwap),
+            Square::Occupied(owner, _, _) => self.swap(owner, positions),
+        }
+    }
+
+    // What each position would hold after the swap, without making it
+    pub fn preview_swap(
+        &self,
+        player: usize,
+        positions: [Coordinate; 2],
+    ) -> Result<[(Coordinate, Square); 2], GamePlayError> {
         if positions[0] == positions[1] {
             return Err(GamePlayError::SelfSwap);
         }
+        // A root always keeps the first tile placed on it
+        if positions
+            .iter()
+            .any(|position| self.roots.contains(position))
+        {
+            return Err(GamePlayError::RootSwap);
+        }
 
-        let mut tiles = ['&'; 2];
+        let mut tiles = [Square::Empty; 2];
         for (i, pos) in positions.iter().enumerate() {
             match self.get(*pos)? {
                 Square::Empty => return Err(GamePlayError::UnoccupiedSwap),
-                Square::Occupied(owner, tile) => {
-                    if owner != player {
-                        return Err(GamePlayError::UnownedSwap);
-                    }
-                    tiles[i] = tile;
+                Square::Neutral(_) => return Err(GamePlayError::UnownedSwap),
+                Square::Occupied(owner, ..) if owner != player => {
+                    return Err(GamePlayError::UnownedSwap)
                 }
+                tile => tiles[i] = tile,
             };
         }
 
-        self.set(positions[0], player, tiles[1])?;
-        self.set(positions[1], player, tiles[0])?;
-
-        Ok(())
+        Ok([(positions[0], tiles[1]), (positions[1], tiles[0])])
     }
 
     pub fn get_words(&self, position: Coordinate) -> Vec<Vec<Coordinate>> {
         let mut words: Vec<Vec<Coordinate>> = Vec::new();
         let owner = match self.get(position) {
-            Ok(Square::Occupied(player, _)) => player,
+            Ok(Square::Occupied(player, _, _)) => player,
             _ => return words,
         };
 
@@ -225,7 +695,7 @@ impl Board {
                 let fowards = direction == Direction::South || direction == Direction::East;
                 let mut location = position.add(direction);
 
-                while let Ok(Square::Occupied(player, _)) = self.get(location) {
+                while let Ok(Square::Occupied(player, _, _)) = self.get(location) {
                     if player != owner {
                         break;
                     }
@@ -259,35 +729,12 @@ impl Board {
 
     pub fn word_strings(
         &self,
-        coordinates: &Vec<Vec<Coordinate>>,
+        coordinates: &[Vec<Coordinate>],
     ) -> Result<Vec<String>, GamePlayError> {
-        let mut err = None; // TODO: is this a reasonable error handling method? We can't return an Err from the function from within the closure passed to map.
-        let strings = coordinates
+        coordinates
             .iter()
-            .map(|word| {
-                word.iter()
-                    .map(|&square| match self.get(square) {
-                        Ok(sq) => match sq {
-                            Square::Empty => {
-                                err = Some(GamePlayError::EmptySquareInWord);
-                                '_'
-                            }
-                            Square::Occupied(_, letter) => letter,
-                        },
-                        Err(e) => {
-                            err = Some(e);
-                            '_'
-                        }
-                    })
-                    .collect::<String>()
-            })
-            .collect::<Vec<String>>();
-
-        if let Some(err_string) = err {
-            Err(err_string)
-        } else {
-            Ok(strings)
-        }
+            .map(|word| word_from_squares(self, word))
+            .collect()
     }
 
     pub fn get_orientations(&self) -> &Vec<Direction> {
@@ -312,14 +759,220 @@ impl Board {
                     y: (self.height() - 2),
                 })
                 .collect(),
-            Direction::East => (0..self.width())
+            Direction::East => (0..self.height())
                 .map(|y| Coordinate {
                     x: (self.width() - 2),
                     y,
                 })
                 .collect(),
-            Direction::West => (0..self.width()).map(|y| Coordinate { x: 1, y }).collect(),
+            Direction::West => (0..self.height()).map(|y| Coordinate { x: 1, y }).collect(),
+        }
+    }
+
+    // The fewest tiles the player would have to place to connect their root to their goal edge, or None if they're walled off.
+    // Empty squares cost a tile each, the player's own tiles are free, and other players' tiles are impassable.
+    pub fn distance_to_goal(&self, player: usize) -> Option<usize> {
+        let root = self.roots.get(player)?;
+        let goal: HashSet<Coordinate> = self
+            .goal_edges(player)
+            .into_iter()
+            .flat_map(|side| self.get_near_edge(side))
+            .collect();
+        self.fill_distance(player, *root, |position| goal.contains(&position))
+    }
+
+    // The fewest empty squares the player would have to fill to join the squares at `a` and `b` through their own tiles,
+    // or None if other players' tiles or dead squares wall them apart.
+    pub fn gap_between_groups(&self, player: usize, a: Coordinate, b: Coordinate) -> Option<usize> {
+        self.fill_distance(player, a, |position| position == b)
+    }
+
+    // The empty squares that would have to be filled, and with what, to spell the target from the start square in the given direction.
+    // Errors if a tile already on the line is the wrong letter, or if the word runs off the board.
+    pub fn missing_tiles_for(
+        &self,
+        start: Coordinate,
+        direction: Direction,
+        target: &str,
+    ) -> Result<Vec<(Coordinate, char)>, GamePlayError> {
+        let mut missing = Vec::new();
+        let mut position = start;
+        for (index, expected) in target.chars().enumerate() {
+            if index > 0 {
+                position = position.add(direction);
+            }
+            match self.get(position)? {
+                Square::Empty => missing.push((position, expected)),
+                Square::Occupied(_, found, _) | Square::Neutral(found)
+                    if found != letter_of(expected) =>
+                {
+                    return Err(GamePlayError::ConflictingTile {
+                        position,
+                        expected,
+                        found,
+                    })
+                }
+                _ => {}
+            }
         }
+        Ok(missing)
+    }
+
+    // How many squares hold something different on the other board, for telling how far apart two positions are
+    pub fn edit_distance(&self, other: &Board) -> Result<usize, GamePlayError> {
+        if (self.width(), self.height()) != (other.width(), other.height()) {
+            return Err(GamePlayError::MismatchedDimensions {
+                expected: (self.width(), self.height()),
+                found: (other.width(), other.height()),
+            });
+        }
+        Ok(self
+            .squares
+            .iter()
+            .flatten()
+            .zip(other.squares.iter().flatten())
+            .filter(|(ours, theirs)| ours != theirs)
+            .count())
+    }
+
+    // The fraction of squares that are the same on both boards, from 0 for entirely different boards to 1 for identical ones
+    pub fn similarity(&self, other: &Board) -> Result<f64, GamePlayError> {
+        let squares = self.width() * self.height();
+        if squares == 0 {
+            return self.edit_distance(other).map(|_| 1.0);
+        }
+        Ok(1.0 - self.edit_distance(other)? as f64 / squares as f64)
+    }
+
+    // Whether every player is racing to their goal from the same distance, which tends to end in a tie
+    pub fn symmetric_threats(&self) -> bool {
+        let distances: Vec<Option<usize>> = (0..self.roots.len())
+            .map(|player| self.distance_to_goal(player))
+            .collect();
+        match distances.first() {
+            Some(Some(first)) => distances.iter().all(|d| *d == Some(*first)),
+            _ => false,
+        }
+    }
+
+    // Shared by the distances above, counting the start square itself if it's empty
+    fn fill_distance<F: Fn(Coordinate) -> bool>(
+        &self,
+        player: usize,
+        start: Coordinate,
+        is_goal: F,
+    ) -> Option<usize> {
+        let cost = |position: Coordinate| match self.get(position) {
+            Ok(Square::Empty) => Some(1),
+            Ok(Square::Occupied(owner, _, _)) if owner == player => Some(0),
+            _ => None,
+        };
+
+        // 0-1 breadth first search, where free squares are explored before costly ones
+        let mut distances = HashMap::new();
+        let mut queue = VecDeque::new();
+        distances.insert(start, cost(start)?);
+        queue.push_back(start);
+        while let Some(position) = queue.pop_front() {
+            let distance = distances[&position];
+            if is_goal(position) {
+                return Some(distance);
+            }
+            for (neighbour, _) in self.neighbouring_squares(position) {
+                if let Some(step) = cost(neighbour) {
+                    if distances
+                        .get(&neighbour)
+                        .is_none_or(|&known| distance + step < known)
+                    {
+                        distances.insert(neighbour, distance + step);
+                        if step == 0 {
+                            queue.push_front(neighbour);
+                        } else {
+                            queue.push_back(neighbour);
+                        }
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    // The empty squares the player could fill within `depth` placements, ignoring any battles along the way
+    pub fn reachable_within(&self, player: usize, depth: usize) -> HashSet<Coordinate> {
+        let empty_neighbours = |position: Coordinate| {
+            self.neighbouring_squares(position)
+                .into_iter()
+                .filter(|&(_, square)| square == Square::Empty)
+                .map(|(neighbour, _)| neighbour)
+        };
+
+        let mut frontier: HashSet<Coordinate> = self
+            .tiles_of(player)
+            .into_iter()
+            .flat_map(|(position, _)| empty_neighbours(position))
+            .collect();
+        if let Some(&root) = self.roots.get(player) {
+            if let Ok(Square::Empty) = self.get(root) {
+                frontier.insert(root);
+            }
+        }
+
+        let mut reached = HashSet::new();
+        for _ in 0..depth {
+            reached.extend(frontier.iter().copied());
+            frontier = frontier
+                .into_iter()
+                .flat_map(empty_neighbours)
+                .filter(|position| !reached.contains(position))
+                .collect();
+        }
+        reached
+    }
+
+    // Renders the board like `to_string`, but with every letter in the given case
+    pub fn to_string_case(&self, case: Case) -> String {
+        self.render_squares(
+            |sq| match case {
+                Case::Upper => sq.to_string().to_uppercase(),
+                Case::Lower => sq.to_string().to_lowercase(),
+            },
+            |_, s| s,
+        )
+    }
+
+    // Renders the board like `to_string`, but with each tile followed by its owner, e.g. `A0` or `A1`, padding every square to the same width to keep the columns lined up
+    pub fn to_ownership_string(&self) -> String {
+        let cells: Vec<Vec<String>> = self
+            .squares
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|opt| match opt {
+                        Some(square @ Square::Occupied(player, ..)) => {
+                            format!("{}{}", square, player)
+                        }
+                        Some(sq) => sq.to_string(),
+                        None => String::new(),
+                    })
+                    .collect()
+            })
+            .collect();
+        let width = cells
+            .iter()
+            .flatten()
+            .map(|cell| cell.chars().count())
+            .max()
+            .unwrap_or(0);
+        cells
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|cell| format!("{:<width$}", cell, width = width))
+                    .collect::<Vec<String>>()
+                    .join(" ")
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
     }
 
     pub fn render_squares<F: Fn(&Square) -> String, G: Fn(usize, String) -> String>(
@@ -345,6 +998,38 @@ impl Board {
     }
 }
 
+// A pseudorandom key for a square's contents, derived from the contents rather than looked up in a table so that it works for any board size and letter.
+// Empty squares have no key, so a board's hash is the XOR of the keys of its tiles.
+fn zobrist_key(position: Coordinate, square: Square) -> u64 {
+    let (kind, letter) = match square {
+        Square::Empty => return 0,
+        Square::Neutral(letter) => (0, letter),
+        Square::Occupied(player, letter, blank) => {
+            ((player as u64 + 1) << 1 | blank as u64, letter)
+        }
+    };
+    // The splitmix64 finalizer, which spreads every input bit across the whole key
+    let mut z = (position.x as u64 & 0xFFFF)
+        | (position.y as u64 & 0xFFFF) << 16
+        | (kind & 0xFF) << 32
+        | (letter as u64) << 40;
+    z = z.wrapping_add(0x9E3779B97F4A7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    z ^ (z >> 31)
+}
+
+// Reads the letters along a run of squares, which must all be occupied
+pub fn word_from_squares(board: &Board, squares: &[Coordinate]) -> Result<String, GamePlayError> {
+    squares
+        .iter()
+        .map(|&square| match board.get(square)? {
+            Square::Empty => Err(GamePlayError::EmptySquareInWord),
+            Square::Occupied(_, letter, _) | Square::Neutral(letter) => Ok(letter), // Blanks spell the letter they were played as
+        })
+        .collect()
+}
+
 impl Default for Board {
     fn default() -> Self {
         Self::new(9, 9)
@@ -357,6 +1042,14 @@ impl fmt::Display for Board {
     }
 }
 
+// How letters are shown when rendering a board, independent of how they're stored
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Case {
+    Upper,
+    Lower,
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, PartialOrd, Ord)]
 pub struct Coordinate {
     pub x: usize,
@@ -392,32 +1085,46 @@ impl fmt::Display for Coordinate {
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Square {
     Empty,
-    Occupied(usize, char),
+    Occupied(usize, char, bool), // The owner, the letter, and whether the tile is a blank played as that letter
+    Neutral(char),               // An obstacle owned by nobody, which any player can capture
 }
 
 impl fmt::Display for Square {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match &self {
             Square::Empty => write!(f, "_"),
-            Square::Occupied(_, tile) => write!(f, "{}", tile),
+            Square::Occupied(_, letter, true) => write!(f, "{}", letter.to_ascii_lowercase()), // Blanks are written in lowercase, as in Scrabble
+            Square::Occupied(_, tile, _) | Square::Neutral(tile) => write!(f, "{}", tile),
         }
     }
 }
 
 impl Square {
-    pub fn to_oriented_string(&self, orientations: &Vec<Direction>) -> String {
-        match &self {
+    // The tile that would go back in the bag if this square were cleared, which for a blank is a blank.
+    // Neutral tiles never came from the bag, so only players' tiles have one.
+    pub fn tile(self) -> Option<char> {
+        match self {
+            Square::Occupied(_, _, true) => Some(BLANK),
+            Square::Occupied(_, letter, false) => Some(letter),
+            _ => None,
+        }
+    }
+
+    pub fn to_oriented_string(self, orientations: &[Direction]) -> String {
+        match self {
             Square::Empty => String::from("_"),
-            Square::Occupied(player, tile) => {
+            Square::Neutral(tile) => tile.to_string(),
+            Square::Occupied(player, tile, _) => {
                 if *(orientations
-                    .get(*player)
+                    .get(player)
                     .expect("Should only pass valid players"))
                     == Direction::North
                 {
-                    Self::flip(tile).to_string()
+                    Self::flip(&tile).to_string()
                 } else {
                     tile.to_string()
                 }
@@ -437,7 +1144,9 @@ impl Square {
 
 #[cfg(test)]
 pub mod tests {
+    use super::super::bag::tests as TileUtils;
     use super::*;
+    use crate::judge::Judge;
 
     #[test]
     fn makes_default_boards() {
@@ -467,6 +1176,24 @@ pub mod tests {
         );
     }
 
+    #[test]
+    fn symmetric_boards() {
+        for (width, height) in [(1, 1), (2, 1), (3, 4), (6, 1), (9, 9)] {
+            let b = Board::symmetric(width, height);
+            let [first, second] = [b.get_root(0).unwrap(), b.get_root(1).unwrap()];
+            assert_eq!(
+                second,
+                Coordinate {
+                    x: b.width() - 1 - first.x,
+                    y: b.height() - 1 - first.y
+                }
+            );
+            let orientations = b.get_orientations();
+            assert_eq!(orientations[0].opposite(), orientations[1]);
+            assert_eq!(b.validate(), Ok(()));
+        }
+    }
+
     #[test]
     fn width_height() {
         let b = Board::new(6, 1);
@@ -542,47 +1269,239 @@ pub mod tests {
     fn set_changes_get() {
         let mut b = Board::new(1, 1); // Note, height is 3 from home rows
         assert_eq!(b.get(Coordinate { x: 0, y: 0 }), Ok(Square::Empty));
+        assert_eq!(b.set(Coordinate { x: 0, y: 0 }, 0, 'A'), Ok(()));
+        assert_eq!(
+            b.get(Coordinate { x: 0, y: 0 }),
+            Ok(Square::Occupied(0, 'A', false))
+        );
+
+        // Lowercase letters are blanks played as that letter
         assert_eq!(b.set(Coordinate { x: 0, y: 0 }, 0, 'a'), Ok(()));
         assert_eq!(
-            b.get(Coordinate { x: 0, y: 0 }),
-            Ok(Square::Occupied(0, 'a'))
+            b.get(Coordinate { x: 0, y: 0 }),
+            Ok(Square::Occupied(0, 'A', true))
+        );
+    }
+
+    #[test]
+    fn depth_first_search() {
+        let mut b = Board::new(3, 1);
+
+        // Create a connected tree
+        let parts = [
+            Coordinate { x: 2, y: 1 },
+            Coordinate { x: 1, y: 1 },
+            Coordinate { x: 1, y: 0 },
+            Coordinate { x: 0, y: 1 },
+        ];
+        let parts_set = HashSet::from(parts);
+        for part in parts {
+            assert_eq!(b.set(part, 0, 'a'), Ok(()));
+        }
+
+        // The tree should be returned no matter where in the tree we start DFS from
+        for part in parts {
+            assert!(b.depth_first_search(part).is_subset(&parts_set));
+            assert!(b.depth_first_search(part).is_superset(&parts_set));
+        }
+
+        // Set the remaining unoccupied square on the board to be occupied by another player
+        let other = Coordinate { x: 1, y: 2 };
+        // WHen unoccupied it should give the empty set, when occupied, just itself
+        assert!(b.depth_first_search(other).iter().eq([].iter()));
+        assert_eq!(b.set(other, 1, 'a'), Ok(()));
+        assert!(b.depth_first_search(other).iter().eq([other].iter()));
+
+        // The result of DFS on the main tree should not have changed
+        for part in parts {
+            assert!(b.depth_first_search(part).is_subset(&parts_set));
+            assert!(b.depth_first_search(part).is_superset(&parts_set));
+        }
+    }
+
+    #[test]
+    fn truncate_islands_on_other_roots() {
+        let mut b = Board::new(3, 3);
+        let mut hands = Hands::new(2, 7, TileUtils::trivial_bag());
+        b.set(Coordinate { x: 1, y: 4 }, 1, 'D').unwrap();
+        b.set(Coordinate { x: 1, y: 3 }, 1, 'E').unwrap();
+
+        // Player 1's tiles on player 0's root aren't connected to player 1's root, so they're removed
+        b.set(Coordinate { x: 1, y: 0 }, 1, 'B').unwrap();
+        b.set(Coordinate { x: 1, y: 1 }, 1, 'C').unwrap();
+        assert_eq!(
+            b.truncate(&mut hands),
+            vec![
+                (Coordinate { x: 1, y: 0 }, 'B'),
+                (Coordinate { x: 1, y: 1 }, 'C')
+            ]
+        );
+        assert_eq!(
+            b.to_string(),
+            ["  _  ", "_ _ _", "_ _ _", "_ E _", "  D  "].join("\n")
+        );
+    }
+
+    #[test]
+    fn truncate_from_custom_anchors() {
+        let mut b = from_string(
+            [
+                "_ A _ _ _",
+                "_ B _ C _",
+                "_ _ _ D _",
+                "_ E _ _ _",
+                "_ F _ _ _",
+            ]
+            .join("\n"),
+            vec![Coordinate { x: 1, y: 0 }],
+            vec![Direction::North],
+        )
+        .unwrap();
+        let mut hands = Hands::new(1, 7, TileUtils::trivial_bag());
+        let mut test_hands = Hands::new(1, 7, TileUtils::trivial_bag());
+
+        // Anchoring at the second column keeps both of its clumps, even though the bottom one isn't connected to the root
+        b.truncate_from(
+            &[Coordinate { x: 1, y: 0 }, Coordinate { x: 1, y: 4 }],
+            &mut hands,
+        );
+        for letter in ['C', 'D'] {
+            test_hands.return_tile(letter);
+        }
+        assert_eq!(hands, test_hands);
+        assert_eq!(
+            b.to_string(),
+            [
+                "_ A _ _ _",
+                "_ B _ _ _",
+                "_ _ _ _ _",
+                "_ E _ _ _",
+                "_ F _ _ _",
+            ]
+            .join("\n"),
+        );
+
+        // Anchoring at an empty square removes everything
+        b.truncate_from(&[Coordinate { x: 4, y: 4 }], &mut hands);
+        for letter in ['A', 'B', 'E', 'F'] {
+            test_hands.return_tile(letter);
+        }
+        assert_eq!(hands, test_hands);
+        assert!(b.to_string().chars().all(|c| c == '_' || c.is_whitespace()));
+    }
+
+    #[test]
+    fn tiles_of() {
+        let b = from_string(
+            [
+                "_ _ A _ _",
+                "_ _ B C _",
+                "_ _ _ _ _",
+                "_ D _ _ _",
+                "_ E F _ _",
+            ]
+            .join("\n"),
+            vec![Coordinate { x: 2, y: 0 }, Coordinate { x: 2, y: 4 }],
+            vec![Direction::North, Direction::South],
+        )
+        .unwrap();
+
+        assert_eq!(
+            b.tiles_of(0),
+            vec![
+                (Coordinate { x: 2, y: 0 }, 'A'),
+                (Coordinate { x: 2, y: 1 }, 'B'),
+                (Coordinate { x: 3, y: 1 }, 'C'),
+            ]
+        );
+        assert_eq!(
+            b.tiles_of(1),
+            vec![
+                (Coordinate { x: 1, y: 3 }, 'D'),
+                (Coordinate { x: 1, y: 4 }, 'E'),
+                (Coordinate { x: 2, y: 4 }, 'F'),
+            ]
+        );
+        assert_eq!(b.tiles_of(2), vec![]);
+    }
+
+    #[test]
+    fn orphan_tiles() {
+        let mut b = from_string(
+            [
+                "_ _ A _ _",
+                "_ _ B _ _",
+                "_ _ _ _ _",
+                "_ _ _ _ _",
+                "_ _ D _ _",
+            ]
+            .join("\n"),
+            vec![Coordinate { x: 2, y: 0 }, Coordinate { x: 2, y: 4 }],
+            vec![Direction::North, Direction::South],
+        )
+        .unwrap();
+        // A stray tile that only touches empty squares, as on a hand built board
+        b.set(Coordinate { x: 4, y: 2 }, 0, 'X').unwrap();
+
+        assert_eq!(b.orphan_tiles(0), vec![Coordinate { x: 4, y: 2 }]);
+        // A lone root tile is an orphan too
+        assert_eq!(b.orphan_tiles(1), vec![Coordinate { x: 2, y: 4 }]);
+        assert_eq!(b.orphan_tiles(2), vec![]);
+    }
+
+    #[test]
+    fn validate() {
+        let mut b = from_string(
+            [
+                "_ _ A _ _",
+                "_ _ B _ _",
+                "_ _ _ _ _",
+                "_ _ C _ _",
+                "_ _ D _ _",
+            ]
+            .join("\n"),
+            vec![Coordinate { x: 2, y: 0 }, Coordinate { x: 2, y: 4 }],
+            vec![Direction::North, Direction::South],
+        )
+        .unwrap();
+        assert_eq!(b.validate(), Ok(()));
+        assert_eq!(Board::default().validate(), Ok(()));
+
+        // A floating tile that isn't connected to any root
+        let floating = Coordinate { x: 0, y: 2 };
+        b.set(floating, 0, 'E').unwrap();
+        assert_eq!(
+            b.validate(),
+            Err(GamePlayError::DisconnectedTile { position: floating })
         );
-    }
-
-    #[test]
-    fn depth_first_search() {
-        let mut b = Board::new(3, 1);
-
-        // Create a connected tree
-        let parts = [
-            Coordinate { x: 2, y: 1 },
-            Coordinate { x: 1, y: 1 },
-            Coordinate { x: 1, y: 0 },
-            Coordinate { x: 0, y: 1 },
-        ];
-        let parts_set = HashSet::from(parts);
-        for part in parts {
-            assert_eq!(b.set(part, 0, 'a'), Ok(()));
-        }
 
-        // The tree should be returned no matter where in the tree we start DFS from
-        for part in parts {
-            assert!(b.depth_first_search(part).is_subset(&parts_set));
-            assert!(b.depth_first_search(part).is_superset(&parts_set));
-        }
+        // A tile touching another player's group
+        b.clear(floating);
+        let intruder = Coordinate { x: 2, y: 2 };
+        b.set(intruder, 1, 'E').unwrap();
+        assert_eq!(b.validate(), Ok(()));
+        b.set(intruder, 0, 'E').unwrap();
+        b.set(Coordinate { x: 2, y: 1 }, 1, 'B').unwrap();
+        assert_eq!(
+            b.validate(),
+            Err(GamePlayError::DisconnectedTile {
+                position: Coordinate { x: 2, y: 1 }
+            })
+        );
 
-        // Set the remaining unoccupied square on the board to be occupied by another player
-        let other = Coordinate { x: 1, y: 2 };
-        // WHen unoccupied it should give the empty set, when occupied, just itself
-        assert!(b.depth_first_search(other).iter().eq([].iter()));
-        assert_eq!(b.set(other, 1, 'a'), Ok(()));
-        assert!(b.depth_first_search(other).iter().eq([other].iter()));
+        // A tile owned by a player without a root
+        let mut b = Board::new(3, 1);
+        b.squares[1][1] = Some(Square::Occupied(2, 'A', false));
+        assert_eq!(
+            b.validate(),
+            Err(GamePlayError::NonExistentPlayer { index: 2 })
+        );
 
-        // The result of DFS on the main tree should not have changed
-        for part in parts {
-            assert!(b.depth_first_search(part).is_subset(&parts_set));
-            assert!(b.depth_first_search(part).is_superset(&parts_set));
-        }
+        // A tile written without updating the board's hash
+        let mut b = Board::new(3, 1);
+        b.squares[1][1] = Some(Square::Occupied(0, 'A', false));
+        b.squares[0][1] = Some(Square::Occupied(0, 'B', false));
+        assert_eq!(b.validate(), Err(GamePlayError::StaleHash));
     }
 
     #[test]
@@ -631,19 +1550,83 @@ pub mod tests {
         let c1_1 = Coordinate { x: 1, y: 1 };
         let c2_1 = Coordinate { x: 2, y: 1 };
         assert_eq!(b.set(c0_1, 0, 'a'), Ok(()));
-        assert_eq!(b.set(c1_1, 0, 'b'), Ok(()));
-        assert_eq!(b.set(c2_1, 1, 'c'), Ok(()));
+        assert_eq!(b.set(c1_1, 0, 'B'), Ok(()));
+        assert_eq!(b.set(c2_1, 1, 'C'), Ok(()));
 
-        assert_eq!(b.get(c0_1), Ok(Square::Occupied(0, 'a')));
-        assert_eq!(b.get(c1_1), Ok(Square::Occupied(0, 'b')));
+        // A blank stays a blank as it moves
+        assert_eq!(b.get(c0_1), Ok(Square::Occupied(0, 'A', true)));
+        assert_eq!(b.get(c1_1), Ok(Square::Occupied(0, 'B', false)));
         assert_eq!(b.swap(0, [c0_1, c1_1]), Ok(()));
-        assert_eq!(b.get(c0_1), Ok(Square::Occupied(0, 'b')));
-        assert_eq!(b.get(c1_1), Ok(Square::Occupied(0, 'a')));
+        assert_eq!(b.get(c0_1), Ok(Square::Occupied(0, 'B', false)));
+        assert_eq!(b.get(c1_1), Ok(Square::Occupied(0, 'A', true)));
         assert_eq!(b.swap(0, [c0_1, c0_1]), Err(GamePlayError::SelfSwap));
         assert_eq!(b.swap(0, [c0_1, c2_1]), Err(GamePlayError::UnownedSwap));
         assert_eq!(b.swap(1, [c0_1, c1_1]), Err(GamePlayError::UnownedSwap));
     }
 
+    #[test]
+    fn swaps_keep_tiles_connected() {
+        let b = from_string(
+            [
+                "_ _ A _ _",
+                "_ B C D _",
+                "_ E _ F _",
+                "_ _ _ G _",
+                "_ _ H _ _",
+            ]
+            .join("\n"),
+            vec![Coordinate { x: 2, y: 0 }, Coordinate { x: 2, y: 4 }],
+            vec![Direction::North, Direction::South],
+        )
+        .unwrap();
+        assert_eq!(b.validate(), Ok(()));
+
+        let swaps = b.legal_swaps(0);
+        assert_eq!(swaps.len(), 15); // Any two of the six tiles off the root
+        for swap in swaps {
+            let mut swapped = b.clone();
+            if let crate::moves::Move::Swap { player, positions } = swap {
+                swapped.swap(player, positions).unwrap();
+            }
+            assert_eq!(swapped.validate(), Ok(()));
+            assert_eq!(
+                swapped
+                    .tiles_of(0)
+                    .iter()
+                    .map(|(position, _)| *position)
+                    .collect::<Vec<_>>(),
+                b.tiles_of(0)
+                    .iter()
+                    .map(|(position, _)| *position)
+                    .collect::<Vec<_>>()
+            );
+        }
+    }
+
+    #[test]
+    fn preview_swap() {
+        let mut b = Board::new(3, 1);
+        let c0_1 = Coordinate { x: 0, y: 1 };
+        let c1_1 = Coordinate { x: 1, y: 1 };
+        let c2_1 = Coordinate { x: 2, y: 1 };
+        b.set(c0_1, 0, 'a').unwrap();
+        b.set(c1_1, 0, 'b').unwrap();
+        b.set(c2_1, 1, 'c').unwrap();
+
+        let unchanged = b.clone();
+        let preview = b.preview_swap(0, [c0_1, c1_1]).unwrap();
+        assert_eq!(b, unchanged);
+        b.swap(0, [c0_1, c1_1]).unwrap();
+        for (position, square) in preview {
+            assert_eq!(b.get(position), Ok(square));
+        }
+
+        assert_eq!(
+            b.preview_swap(0, [c0_1, c2_1]),
+            Err(GamePlayError::UnownedSwap)
+        );
+    }
+
     #[test]
     fn get_words() {
         // Should return an empty list of words for all points on an empty board, and for positions off the board
@@ -705,7 +1688,7 @@ pub mod tests {
         .unwrap();
         assert_eq!(
             b.get(Coordinate { x: 2, y: 4 }),
-            Ok(Square::Occupied(0, 'S'))
+            Ok(Square::Occupied(0, 'S', false))
         );
         assert_eq!(b.set(Coordinate { x: 3, y: 4 }, 1, 'O'), Ok(()));
         assert_eq!(b.get_words(Coordinate { x: 2, y: 4 }), vec![cross]); // TODO: check coordinates
@@ -749,6 +1732,72 @@ pub mod tests {
         }
     }
 
+    #[test]
+    fn word_from_squares() {
+        let b = from_string(
+            ["_ _ _ _", "_ F A T", "_ _ _ _"].join("\n"),
+            vec![Coordinate { x: 1, y: 1 }],
+            vec![Direction::South],
+        )
+        .unwrap();
+        let fat: Vec<Coordinate> = (1..=3).map(|x| Coordinate { x, y: 1 }).collect();
+        assert_eq!(super::word_from_squares(&b, &fat), Ok(String::from("FAT")));
+
+        let reversed: Vec<Coordinate> = fat.iter().rev().copied().collect();
+        assert_eq!(
+            super::word_from_squares(&b, &reversed),
+            Ok(String::from("TAF"))
+        );
+
+        assert_eq!(
+            super::word_from_squares(&b, &[Coordinate { x: 0, y: 1 }, fat[0]]),
+            Err(GamePlayError::EmptySquareInWord)
+        );
+        let outside = Coordinate { x: 4, y: 1 };
+        assert_eq!(
+            super::word_from_squares(&b, &[fat[2], outside]),
+            Err(GamePlayError::OutSideBoardDimensions { position: outside })
+        );
+    }
+
+    #[test]
+    fn word_strings_follow_changes() {
+        let mut b = from_string(
+            ["_ _ _ _", "_ F A T", "_ _ _ _"].join("\n"),
+            vec![Coordinate { x: 1, y: 1 }],
+            vec![Direction::South],
+        )
+        .unwrap();
+        let fat: Vec<Coordinate> = (1..=3).map(|x| Coordinate { x, y: 1 }).collect();
+        let tail = vec![fat[1], fat[2]];
+        let words = [fat.clone(), tail.clone()];
+        let fresh = |b: &Board| {
+            words
+                .iter()
+                .map(|word| super::word_from_squares(b, word))
+                .collect::<Result<Vec<_>, _>>()
+        };
+
+        assert_eq!(b.word_strings(&words), fresh(&b));
+
+        b.set(fat[1], 0, 'I').unwrap();
+        assert_eq!(b.word_strings(&words), fresh(&b));
+        assert_eq!(b.word_strings(&words).unwrap(), vec!["FIT", "IT"]);
+
+        b.swap(0, [fat[1], fat[2]]).unwrap();
+        assert_eq!(b.word_strings(&words), fresh(&b));
+        assert_eq!(b.word_strings(&words).unwrap(), vec!["FTI", "TI"]);
+
+        let copy = b.clone();
+        b.clear(fat[2]);
+        assert_eq!(b.word_strings(&words), fresh(&b));
+        assert_eq!(
+            b.word_strings(&words),
+            Err(GamePlayError::EmptySquareInWord)
+        );
+        assert_eq!(copy.word_strings(&words), fresh(&copy));
+    }
+
     #[test]
     fn get_near_edge() {
         let b = Board::new(3, 1);
@@ -788,6 +1837,440 @@ pub mod tests {
         );
     }
 
+    #[test]
+    fn distance_to_goal() {
+        let b = Board::new(5, 5);
+        assert_eq!(b.distance_to_goal(0), Some(6)); // The root plus a tile in each of the 5 rows
+        assert_eq!(b.distance_to_goal(1), Some(6));
+        assert_eq!(b.distance_to_goal(2), None);
+
+        let b = from_string(
+            [
+                "    A    ",
+                "_ _ A _ _",
+                "_ _ _ _ _",
+                "B B B B B",
+                "_ _ B _ _",
+                "    B    ",
+            ]
+            .join("\n"),
+            vec![Coordinate { x: 2, y: 0 }, Coordinate { x: 2, y: 5 }],
+            vec![Direction::North, Direction::South],
+        )
+        .unwrap();
+        assert_eq!(b.distance_to_goal(0), None);
+        assert_eq!(b.distance_to_goal(1), Some(2));
+    }
+
+    #[test]
+    fn edges() {
+        let mut b = Board::new(5, 5)
+            .with_edge(Direction::South, EdgeKind::Wall)
+            .with_edge(Direction::West, EdgeKind::Goal(0));
+        assert_eq!(b.goal_edges(0), vec![Direction::West]);
+        assert_eq!(b.goal_edges(1), vec![Direction::North]);
+        assert_eq!(b.distance_to_goal(0), Some(3));
+
+        // Reaching the usual goal doesn't win any more, but reaching the side does
+        for y in 1..=5 {
+            b.set(Coordinate { x: 2, y }, 0, 'A').unwrap();
+        }
+        assert_eq!(Judge::winner(&b), None);
+        b.set(Coordinate { x: 1, y: 5 }, 0, 'A').unwrap();
+        assert_eq!(Judge::winner(&b), Some(0));
+
+        let edge = Coordinate { x: 0, y: 3 };
+        let across = Coordinate { x: 4, y: 3 };
+        let neighbours = |b: &Board| -> Vec<Coordinate> {
+            b.neighbouring_squares(edge)
+                .into_iter()
+                .map(|(position, _)| position)
+                .collect()
+        };
+        assert!(!neighbours(&Board::new(5, 5)).contains(&across));
+
+        // Wrapping one side wraps the side opposite it too, and unwrapping either side unwraps both
+        let wrapped = Board::new(5, 5).with_edge(Direction::East, EdgeKind::Wrap);
+        assert_eq!(wrapped.edge(Direction::West), EdgeKind::Wrap);
+        assert!(neighbours(&wrapped).contains(&across));
+        let unwrapped = wrapped.clone().with_edge(Direction::West, EdgeKind::Wall);
+        assert_eq!(unwrapped.edge(Direction::East), EdgeKind::Wall);
+        assert!(!neighbours(&unwrapped).contains(&across));
+        assert_ne!(wrapped.canonical_hash(), Board::new(5, 5).canonical_hash());
+        assert_eq!(
+            unwrapped.canonical_hash(),
+            Board::new(5, 5).canonical_hash()
+        );
+    }
+
+    #[test]
+    fn wrap_north_and_south() {
+        let b = Board::new(5, 5).with_edge(Direction::North, EdgeKind::Wrap);
+        assert_eq!(b.edge(Direction::South), EdgeKind::Wrap);
+        let neighbours = |position: Coordinate| -> Vec<Coordinate> {
+            b.neighbouring_squares(position)
+                .into_iter()
+                .map(|(position, _)| position)
+                .collect()
+        };
+
+        // The dead squares of the home rows are skipped, so the top and bottom playable rows meet
+        let top = Coordinate { x: 0, y: 1 };
+        let bottom = Coordinate { x: 0, y: 5 };
+        assert!(neighbours(top).contains(&bottom));
+        assert!(neighbours(bottom).contains(&top));
+
+        // Beside a root, the home row is playable, so it's the edge instead
+        let below_root = Coordinate { x: 2, y: 1 };
+        let roots = [b.get_root(0).unwrap(), b.get_root(1).unwrap()];
+        assert!(neighbours(below_root).contains(&roots[0]));
+        assert!(!neighbours(below_root).contains(&Coordinate { x: 2, y: 5 }));
+        assert!(neighbours(roots[0]).contains(&roots[1]));
+    }
+
+    #[test]
+    fn missing_tiles_for() {
+        let b = from_string(
+            [
+                "_ _ A _ _",
+                "_ _ B _ _",
+                "_ _ _ _ _",
+                "_ _ _ _ _",
+                "_ _ _ _ _",
+            ]
+            .join("\n"),
+            vec![Coordinate { x: 2, y: 0 }, Coordinate { x: 2, y: 4 }],
+            vec![Direction::North, Direction::South],
+        )
+        .unwrap();
+        let start = Coordinate { x: 2, y: 0 };
+
+        assert_eq!(
+            b.missing_tiles_for(start, Direction::South, "ABLE"),
+            Ok(vec![
+                (Coordinate { x: 2, y: 2 }, 'L'),
+                (Coordinate { x: 2, y: 3 }, 'E')
+            ])
+        );
+        assert_eq!(
+            b.missing_tiles_for(start, Direction::South, "ACE"),
+            Err(GamePlayError::ConflictingTile {
+                position: Coordinate { x: 2, y: 1 },
+                expected: 'C',
+                found: 'B'
+            })
+        );
+        assert_eq!(
+            b.missing_tiles_for(start, Direction::North, "AB"),
+            Err(GamePlayError::OutSideBoardDimensions {
+                position: Coordinate {
+                    x: 2,
+                    y: usize::MAX
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn edit_distance() {
+        let b = Board::new(5, 5);
+        let mut other = b.clone();
+        other.set(Coordinate { x: 2, y: 0 }, 0, 'A').unwrap();
+        other.set(Coordinate { x: 2, y: 1 }, 0, 'B').unwrap();
+        other.set_neutral(Coordinate { x: 0, y: 3 }, 'X').unwrap();
+
+        assert_eq!(b.edit_distance(&b), Ok(0));
+        assert_eq!(b.edit_distance(&other), Ok(3));
+        assert_eq!(other.edit_distance(&b), Ok(3));
+        assert_eq!(b.similarity(&b), Ok(1.0));
+        assert_eq!(b.similarity(&other), Ok(1.0 - 3.0 / 35.0));
+        assert_eq!(
+            b.edit_distance(&Board::new(3, 3)),
+            Err(GamePlayError::MismatchedDimensions {
+                expected: (5, 7),
+                found: (3, 5)
+            })
+        );
+    }
+
+    #[test]
+    fn changed_since() {
+        let b = Board::new(5, 5);
+        let mut other = b.clone();
+        other.set(Coordinate { x: 2, y: 0 }, 0, 'A').unwrap();
+
+        assert_eq!(b.changed_since(&b), Ok(vec![]));
+        assert_eq!(
+            other.changed_since(&b),
+            Ok(vec![(Coordinate { x: 2, y: 0 }, Square::Empty)])
+        );
+        assert_eq!(
+            b.changed_since(&Board::new(3, 3)),
+            Err(GamePlayError::MismatchedDimensions {
+                expected: (5, 7),
+                found: (3, 5)
+            })
+        );
+    }
+
+    #[test]
+    fn symmetric_threats() {
+        assert!(Board::new(5, 5).symmetric_threats());
+
+        let b = from_string(
+            [
+                "    A    ",
+                "_ _ A _ _",
+                "_ _ A _ _",
+                "_ _ _ _ _",
+                "_ _ B _ _",
+                "    B    ",
+            ]
+            .join("\n"),
+            vec![Coordinate { x: 2, y: 0 }, Coordinate { x: 2, y: 5 }],
+            vec![Direction::North, Direction::South],
+        )
+        .unwrap();
+        assert!(!b.symmetric_threats());
+    }
+
+    #[test]
+    fn reachable_within() {
+        let b = from_string(
+            [
+                "    A    ",
+                "_ _ A _ _",
+                "_ _ _ _ _",
+                "_ B B B _",
+                "_ _ B _ _",
+                "    B    ",
+            ]
+            .join("\n"),
+            vec![Coordinate { x: 2, y: 0 }, Coordinate { x: 2, y: 5 }],
+            vec![Direction::North, Direction::South],
+        )
+        .unwrap();
+        assert_eq!(b.reachable_within(0, 0), HashSet::new());
+        assert_eq!(
+            b.reachable_within(0, 1),
+            HashSet::from([
+                Coordinate { x: 1, y: 1 },
+                Coordinate { x: 3, y: 1 },
+                Coordinate { x: 2, y: 2 },
+            ])
+        );
+        assert_eq!(b.reachable_within(0, 2).len(), 7);
+        assert_eq!(b.reachable_within(0, 3).len(), 9);
+        // The opponent's wall can only be walked around along the edges
+        assert_eq!(b.reachable_within(0, 5).len(), 13);
+        assert_eq!(b.reachable_within(0, 10).len(), 15);
+
+        // An empty root is the only square reachable on an empty board
+        let b = Board::new(5, 5);
+        assert_eq!(
+            b.reachable_within(0, 1),
+            HashSet::from([b.get_root(0).unwrap()])
+        );
+    }
+
+    #[test]
+    fn gap_between_groups() {
+        let b = from_string(
+            [
+                "_ _ A _ _",
+                "_ _ B _ _",
+                "_ _ _ _ _",
+                "_ _ C D _",
+                "_ E _ _ _",
+                "_ F _ _ _",
+            ]
+            .join("\n"),
+            vec![Coordinate { x: 2, y: 0 }, Coordinate { x: 1, y: 5 }],
+            vec![Direction::North, Direction::South],
+        )
+        .unwrap();
+        let top = Coordinate { x: 2, y: 0 };
+        let clump = Coordinate { x: 3, y: 3 }; // C and D were cut off from the root, so still belong to player 0
+
+        // Filling the one square between B and C joins the clumps
+        assert_eq!(b.gap_between_groups(0, top, clump), Some(1));
+        assert_eq!(b.gap_between_groups(0, clump, top), Some(1));
+        assert_eq!(
+            b.gap_between_groups(0, top, Coordinate { x: 2, y: 1 }),
+            Some(0)
+        );
+        assert_eq!(
+            b.gap_between_groups(0, top, Coordinate { x: 4, y: 5 }),
+            Some(4)
+        );
+        // Squares held by another player can't be joined through or to
+        assert_eq!(
+            b.gap_between_groups(0, top, Coordinate { x: 1, y: 5 }),
+            None
+        );
+        assert_eq!(
+            b.gap_between_groups(1, Coordinate { x: 1, y: 5 }, top),
+            None
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serialization_is_deterministic() {
+        let board = || {
+            let mut b = Board::new(4, 3);
+            b.set(Coordinate { x: 1, y: 0 }, 0, 'A').unwrap();
+            b.set(Coordinate { x: 1, y: 1 }, 0, 'B').unwrap();
+            b.set(Coordinate { x: 2, y: 4 }, 1, 'C').unwrap();
+            b
+        };
+
+        let b = board();
+        let json = serde_json::to_string(&b).unwrap();
+        assert_eq!(serde_json::to_string(&b).unwrap(), json);
+
+        // Equal boards built separately serialize identically
+        let other = board();
+        assert_eq!(other, b);
+        assert_eq!(serde_json::to_string(&other).unwrap(), json);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serialization_round_trips() {
+        // The board from the judge's win_condition test
+        let mut b = from_string(
+            [
+                "    X    ",
+                "X X X _ _",
+                "X _ _ _ _",
+                "X _ _ _ _",
+                "_ _ _ _ _",
+                "    _    ",
+            ]
+            .join("\n"),
+            vec![Coordinate { x: 0, y: 0 }],
+            vec![Direction::North],
+        )
+        .unwrap()
+        .with_edge(Direction::East, EdgeKind::Wrap);
+        b.set(Coordinate { x: 0, y: 4 }, 0, 'X').unwrap();
+
+        let json = serde_json::to_string(&b).unwrap();
+        assert!(!json.contains("zobrist"));
+        let restored: Board = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, b);
+        assert_eq!(restored.zobrist_hash(), b.zobrist_hash());
+        assert_eq!(restored.roots, b.roots);
+        assert_eq!(restored.get_orientations(), b.get_orientations());
+        assert_eq!(
+            restored.get(Coordinate { x: 0, y: 4 }),
+            Ok(Square::Occupied(0, 'X', false))
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn edited_serializations_are_rehashed() {
+        let mut b = Board::new(3, 1);
+        b.set(Coordinate { x: 1, y: 0 }, 0, 'A').unwrap();
+        let json = serde_json::to_string(&b).unwrap();
+
+        // Changing the letter by hand still gives a consistent board
+        let tile = r#"{"Occupied":[0,"A",false]}"#;
+        assert!(json.contains(tile));
+        let edited: Board =
+            serde_json::from_str(&json.replace(tile, r#"{"Occupied":[0,"B",false]}"#)).unwrap();
+        assert_eq!(
+            edited.get(Coordinate { x: 1, y: 0 }),
+            Ok(Square::Occupied(0, 'B', false))
+        );
+        assert_eq!(edited.validate(), Ok(()));
+        assert_eq!(edited.zobrist_hash(), edited.recompute_zobrist());
+        assert_ne!(edited.zobrist_hash(), b.zobrist_hash());
+    }
+
+    #[test]
+    fn zobrist_hash() {
+        let a = Coordinate { x: 1, y: 1 };
+        let b = Coordinate { x: 1, y: 2 };
+        let empty = Board::new(3, 3);
+        assert_eq!(empty.zobrist_hash(), 0);
+
+        // The order tiles are placed in doesn't matter
+        let mut first = empty.clone();
+        first.set(a, 0, 'A').unwrap();
+        first.set(b, 0, 'B').unwrap();
+        let mut second = empty.clone();
+        second.set(b, 0, 'B').unwrap();
+        second.set(a, 0, 'A').unwrap();
+        assert_eq!(first, second);
+        assert_eq!(first.zobrist_hash(), second.zobrist_hash());
+        assert_eq!(first.zobrist_hash(), first.recompute_zobrist());
+
+        // Changing the owner, the letter, or the kind of tile changes the hash
+        let mut hashes = HashSet::from([first.zobrist_hash()]);
+        second.set(a, 1, 'A').unwrap();
+        assert!(hashes.insert(second.zobrist_hash()));
+        second.set(a, 0, 'C').unwrap();
+        assert!(hashes.insert(second.zobrist_hash()));
+        second.set_neutral(a, 'A').unwrap();
+        assert!(hashes.insert(second.zobrist_hash()));
+        assert_eq!(second.zobrist_hash(), second.recompute_zobrist());
+
+        // Clearing tiles takes their keys back out
+        second.clear(a);
+        second.clear(b);
+        assert_eq!(second.zobrist_hash(), 0);
+
+        // Boards built from strings start with the right hash
+        let mut b = from_string(
+            ["_ A _", "_ B _", "_ C _"].join("\n"),
+            vec![Coordinate { x: 1, y: 0 }, Coordinate { x: 1, y: 2 }],
+            vec![Direction::North, Direction::South],
+        )
+        .unwrap();
+        assert_eq!(b.zobrist_hash(), b.recompute_zobrist());
+        b.clear(Coordinate { x: 1, y: 1 });
+        assert_eq!(b.zobrist_hash(), b.recompute_zobrist());
+    }
+
+    #[test]
+    fn to_string_case() {
+        let mut b = Board::new(3, 1);
+        b.set(Coordinate { x: 1, y: 0 }, 0, 'A').unwrap();
+        b.set(Coordinate { x: 1, y: 1 }, 0, 'b').unwrap();
+        b.set_neutral(Coordinate { x: 0, y: 1 }, 'Ç').unwrap();
+        assert_eq!(
+            b.to_string_case(Case::Upper),
+            ["  A  ", "Ç B _", "  _  "].join("\n")
+        );
+        assert_eq!(
+            b.to_string_case(Case::Lower),
+            ["  a  ", "ç b _", "  _  "].join("\n")
+        );
+        // The stored letters are untouched
+        assert_eq!(b.to_string(), ["  A  ", "Ç b _", "  _  "].join("\n"));
+    }
+
+    #[test]
+    fn to_ownership_string() {
+        let mut b = Board::new(3, 2);
+        b.set(Coordinate { x: 1, y: 0 }, 0, 'A').unwrap();
+        b.set(Coordinate { x: 1, y: 1 }, 0, 'B').unwrap();
+        b.set(Coordinate { x: 1, y: 2 }, 1, 'C').unwrap();
+        b.set_neutral(Coordinate { x: 0, y: 2 }, 'N').unwrap();
+        assert_eq!(
+            b.to_ownership_string(),
+            ["   A0   ", "_  B0 _ ", "N  C1 _ ", "   _    "].join("\n")
+        );
+        // The plain rendering is unchanged
+        assert_eq!(
+            b.to_string(),
+            ["  A  ", "_ B _", "N C _", "  _  "].join("\n")
+        );
+    }
+
     #[test]
     fn flipped() {
         assert_eq!(Square::flip(&'A'), '∀');
@@ -817,7 +2300,11 @@ pub mod tests {
                     .map(|letter| match letter {
                         ' ' => None,
                         '_' => Some(Square::Empty),
-                        letter => Some(Square::Occupied(0, letter)),
+                        letter => Some(Square::Occupied(
+                            0,
+                            letter.to_ascii_uppercase(),
+                            letter.is_lowercase(),
+                        )),
                     })
                     .collect(),
             );
@@ -838,13 +2325,18 @@ pub mod tests {
             roots,
             squares,
             orientations,
+            rules: Rules::default(),
+            edges: BTreeMap::new(),
+            changes: None,
+            zobrist: 0,
         };
+        board.zobrist = board.recompute_zobrist();
         for (player, root) in r.iter().enumerate() {
             if player != 0 {
                 // All tiles are already owned by the first player by default
                 for square in board.depth_first_search(*root).iter() {
-                    if let Ok(Square::Occupied(_, value)) = board.get(*square) {
-                        board.set(*square, player, value).expect(
+                    if let Ok(Square::Occupied(_, value, blank)) = board.get(*square) {
+                        board.restore(*square, Square::Occupied(player, value, blank)).expect(
                             "A coordinate returned from a DFS should always be valid and settable",
                         );
                     }
@@ -915,14 +2407,14 @@ pub mod tests {
             vec![Direction::North; 4],
         )
         .unwrap();
-        assert_eq!(donut.get(top_left), Ok(Square::Occupied(0, 'A')));
-        assert_eq!(donut.get(top_right), Ok(Square::Occupied(1, 'B')));
-        assert_eq!(donut.get(bottom_left), Ok(Square::Occupied(2, 'C')));
+        assert_eq!(donut.get(top_left), Ok(Square::Occupied(0, 'A', false)));
+        assert_eq!(donut.get(top_right), Ok(Square::Occupied(1, 'B', false)));
+        assert_eq!(donut.get(bottom_left), Ok(Square::Occupied(2, 'C', false)));
         assert_eq!(
             donut.get(hole),
             Err(GamePlayError::InvalidPosition { position: hole })
         );
-        assert_eq!(donut.get(dangling), Ok(Square::Occupied(0, 'D')));
+        assert_eq!(donut.get(dangling), Ok(Square::Occupied(0, 'D', false)));
         assert_eq!(donut.get(Coordinate { x: 1, y: 1 }), Ok(Square::Empty));
 
         // Complex trees
@@ -965,10 +2457,16 @@ pub mod tests {
         .unwrap();
 
         for square in player_1 {
-            assert_eq!(complex_tree.get(square), Ok(Square::Occupied(0, 'A')));
+            assert_eq!(
+                complex_tree.get(square),
+                Ok(Square::Occupied(0, 'A', false))
+            );
         }
         for square in player_2 {
-            assert_eq!(complex_tree.get(square), Ok(Square::Occupied(1, 'B')));
+            assert_eq!(
+                complex_tree.get(square),
+                Ok(Square::Occupied(1, 'B', false))
+            );
         }
     }
 }
@@ -0,0 +1,121 @@
+use std::collections::HashSet;
+
+// Somewhere for a judge to look words up. Words are stored exactly as they're given, so they should be normalized first, as the judge does.
+// Dictionaries can be shared between threads, so a judge can be too, e.g. by parallel search code.
+pub trait Dictionary: Send + Sync {
+    fn contains(&self, word: &str) -> bool;
+
+    // Whether any word starts with the prefix, including the prefix itself
+    fn has_prefix(&self, prefix: &str) -> bool;
+}
+
+// Fast exact lookups, but prefix queries have to scan every word, so they take time proportional to the size of the dictionary
+// rather than the length of the prefix. Only `FstDictionary` answers them in time proportional to the prefix.
+impl Dictionary for HashSet<String> {
+    fn contains(&self, word: &str) -> bool {
+        HashSet::contains(self, word)
+    }
+
+    fn has_prefix(&self, prefix: &str) -> bool {
+        self.iter().any(|word| word.starts_with(prefix))
+    }
+}
+
+// A finite state transducer, which shares common prefixes and suffixes so that a large word list takes a fraction of the memory.
+// Both kinds of lookup follow one transition per byte, so they take time proportional to the length of the word.
+#[cfg(feature = "fst")]
+pub struct FstDictionary(fst::Set<Vec<u8>>);
+
+#[cfg(feature = "fst")]
+impl FstDictionary {
+    pub fn size_in_bytes(&self) -> usize {
+        self.0.as_fst().size()
+    }
+}
+
+#[cfg(feature = "fst")]
+impl FromIterator<String> for FstDictionary {
+    fn from_iter<I: IntoIterator<Item = String>>(words: I) -> Self {
+        let mut words: Vec<String> = words.into_iter().collect();
+        words.sort();
+        words.dedup();
+        Self(fst::Set::from_iter(words).expect("Words are sorted and deduplicated"))
+    }
+}
+
+#[cfg(feature = "fst")]
+impl Dictionary for FstDictionary {
+    fn contains(&self, word: &str) -> bool {
+        self.0.contains(word)
+    }
+
+    // Every state in a minimal transducer leads to a word, so the prefix is in use if it can be followed to the end
+    fn has_prefix(&self, prefix: &str) -> bool {
+        let fst = self.0.as_fst();
+        let mut node = fst.root();
+        for byte in prefix.bytes() {
+            match node.find_input(byte) {
+                Some(index) => node = fst.node(node.transition(index).addr),
+                None => return false,
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words() -> Vec<String> {
+        ["BIG", "BIGGER", "FAT", "FOLK"]
+            .iter()
+            .map(|word| word.to_string())
+            .collect()
+    }
+
+    fn check_lookups<D: Dictionary>(dictionary: &D) {
+        assert!(dictionary.contains("BIG"));
+        assert!(dictionary.contains("BIGGER"));
+        assert!(!dictionary.contains("BIGG"));
+        assert!(!dictionary.contains("FO"));
+
+        assert!(dictionary.has_prefix(""));
+        assert!(dictionary.has_prefix("BIGG"));
+        assert!(dictionary.has_prefix("FOLK"));
+        assert!(!dictionary.has_prefix("FOLKS"));
+        assert!(!dictionary.has_prefix("X"));
+    }
+
+    #[test]
+    fn judges_can_be_shared_between_threads() {
+        fn shareable<T: Send + Sync>() {}
+        shareable::<crate::judge::Judge>();
+    }
+
+    #[test]
+    fn hash_set_lookups() {
+        check_lookups(&words().into_iter().collect::<HashSet<String>>());
+    }
+
+    #[cfg(feature = "fst")]
+    #[test]
+    fn fst_lookups() {
+        check_lookups(&words().into_iter().collect::<FstDictionary>());
+    }
+
+    #[cfg(feature = "fst")]
+    #[test]
+    fn fst_is_smaller_than_the_word_list() {
+        let words: Vec<String> = std::fs::read_to_string("./dictionary.txt")
+            .unwrap()
+            .lines()
+            .map(|word| word.to_uppercase())
+            .collect();
+        let letters: usize = words.iter().map(|word| word.len()).sum();
+        let dictionary: FstDictionary = words.into_iter().collect();
+
+        // A hash set needs at least every letter, plus a pointer, length and capacity for each word
+        assert!(dictionary.size_in_bytes() < letters / 2);
+    }
+}
@@ -1,7 +1,9 @@
 use super::board::Coordinate;
 use thiserror::Error;
 
-#[derive(Error, Debug, PartialEq)]
+// Implements std::error::Error, so apps embedding the game can wrap these with their own context, e.g. with anyhow or a
+// thiserror `#[source]` field, and still recover the original variant by downcasting or walking the error's sources.
+#[derive(Error, Clone, Debug, PartialEq, Eq)]
 pub enum GamePlayError {
     #[error("Invalid position ({:?}, {:?})", position.x, position.y)]
     InvalidPosition { position: Coordinate },
@@ -10,6 +12,16 @@ pub enum GamePlayError {
     OutSideBoardDimensions { position: Coordinate },
     #[error("Empty square found in a word, where the word should be an unbroken line of non empty tiles")]
     EmptySquareInWord,
+    #[error("Tile at ({:?}, {:?}) is not connected to its owner's root", position.x, position.y)]
+    DisconnectedTile { position: Coordinate },
+    #[error("The board's stored hash doesn't match its squares")]
+    StaleHash,
+
+    #[error("Can't compare a {}x{} board with a {}x{} board", expected.0, expected.1, found.0, found.1)]
+    MismatchedDimensions {
+        expected: (usize, usize),
+        found: (usize, usize),
+    },
 
     #[error("Player {index:?} does not exist")]
     NonExistentPlayer { index: usize },
@@ -20,12 +32,76 @@ pub enum GamePlayError {
     UnoccupiedSwap,
     #[error("Player must own the squares they swap")]
     UnownedSwap,
+    #[error("Can't swap a tile onto or off of a root")]
+    RootSwap,
+
+    #[error("Square ({:?}, {:?}) holds '{found:?}' where '{expected:?}' is needed", position.x, position.y)]
+    ConflictingTile {
+        position: Coordinate,
+        expected: char,
+        found: char,
+    },
 
     #[error("Cannot place a tile in an occupied square")]
     OccupiedPlace,
     #[error("Must place tile on square that neighbours one of your already placed tiles, or on your root")]
     NonAdjacentPlace,
 
-    #[error("Player {:?} doesn't have a '{tile:?}' tile")]
+    #[error("'{tile:?}' is not a letter in this game's alphabet")]
+    InvalidLetter { tile: char },
+    #[error("Player {player:?} doesn't have a '{tile:?}' tile")]
     PlayerDoesNotHaveTile { player: usize, tile: char },
+    #[error("Player {player:?}'s hand is already full")]
+    HandFull { player: usize },
+
+    #[error("Player {player:?} needs {cost:?} energy for that move, but only has {available:?}")]
+    InsufficientEnergy {
+        player: usize,
+        cost: u32,
+        available: u32,
+    },
+    #[error("Player {player:?} has already placed all {limit:?} of their tiles for this game")]
+    PlacementLimitReached { player: usize, limit: usize },
+    #[error("The game is already over")]
+    GameOver,
+    #[error("Player {player:?} can't move, it's player {next:?}'s turn")]
+    NotYourTurn { player: usize, next: usize },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+    use std::error::Error as _;
+
+    #[derive(Error, Debug)]
+    #[error("Game {game_id} rejected a move")]
+    struct AppError {
+        game_id: u32,
+        #[source]
+        cause: GamePlayError,
+    }
+
+    #[test]
+    fn wrapping_with_context() {
+        let original = GamePlayError::NonExistentPlayer { index: 3 };
+
+        let wrapped = AppError {
+            game_id: 42,
+            cause: original.clone(),
+        };
+        assert_eq!(
+            wrapped.source().unwrap().downcast_ref::<GamePlayError>(),
+            Some(&original)
+        );
+
+        let with_context = Err::<(), _>(original.clone())
+            .context("request 7")
+            .unwrap_err();
+        assert_eq!(with_context.to_string(), "request 7");
+        assert_eq!(
+            with_context.downcast_ref::<GamePlayError>(),
+            Some(&original)
+        );
+    }
 }
@@ -1,7 +1,16 @@
-use super::board::Board;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+
+use super::bag::TileBag;
+use super::board::{Board, Coordinate, Square};
+use super::error::GamePlayError;
 use super::hand::Hands;
+use super::history::{GameLog, SpectatorEvent};
 use super::judge::Judge;
-use super::moves::Move;
+use super::moves::{Move, MoveReport};
+use super::rules::Rules;
+use super::view::GameView;
 
 #[derive(Default)]
 pub struct Game {
@@ -10,22 +19,206 @@ pub struct Game {
     pub judge: Judge,
     next_player: usize,
     winner: Option<usize>,
+    drawn: bool,
+    log: GameLog,
+    events: Vec<SpectatorEvent>,
+    positions: HashMap<u64, usize>, // How many times each position has come up, to detect draws by repetition
+    energy_rules: Option<EnergyRules>,
+    energy: Vec<u32>, // Each player's remaining energy, only used when playing with energy rules
+    peaceful_placements: usize, // How many placements are made before placements start attacking
+    placements: usize, // Placements made so far, including any whose tiles have since left the board
+    placement_limit: Option<usize>, // How many tiles each player may place over the whole game
+    placed: Vec<usize>, // How many tiles each player has placed so far
+    phase_thresholds: PhaseThresholds,
+    resigned: Vec<usize>,
+    scouted: Option<Vec<HashSet<Coordinate>>>, // The squares each player has seen, when playing with fog of war
+}
+
+const REPETITIONS_FOR_DRAW: usize = 3;
+
+// What a move did, and who won if it ended the game
+#[derive(Debug, PartialEq, Eq)]
+pub struct MoveResult {
+    pub report: MoveReport,
+    pub winner: Option<usize>,
+}
+
+// Roughly how far through the game play is, e.g. so that a bot can change its strategy as the game goes on
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Phase {
+    Opening,
+    Midgame,
+    Endgame,
+}
+
+// Where one phase ends and the next begins. Fills are the fraction of the board's squares with tiles on them,
+// and the bag is the fraction of a full bag left before it refills.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PhaseThresholds {
+    pub midgame_fill: f32,
+    pub endgame_fill: f32,
+    pub endgame_bag: f32,
+}
+
+impl Default for PhaseThresholds {
+    fn default() -> Self {
+        Self {
+            midgame_fill: 0.15,
+            endgame_fill: 0.5,
+            endgame_bag: 0.1,
+        }
+    }
+}
+
+// A resource variant where every move spends energy from the player's pool, which refills a little at the start of each of their turns
+#[derive(Clone, Copy, Debug)]
+pub struct EnergyRules {
+    pub starting_energy: u32,
+    pub regeneration: u32,
+    pub cost: fn(&Board, &Move) -> u32, // What a move costs, judged on the board before it's played
+}
+
+impl Default for EnergyRules {
+    fn default() -> Self {
+        Self {
+            starting_energy: 10,
+            regeneration: 2,
+            cost: word_length_cost,
+        }
+    }
+}
+
+// Placements cost the length of the longest word they would form, so building long words takes saving up, and swaps cost one
+pub fn word_length_cost(board: &Board, game_move: &Move) -> u32 {
+    match *game_move {
+        Move::Place {
+            player,
+            tile,
+            position,
+        } => {
+            let mut board = board.clone();
+            if board.set(position, player, tile).is_err() {
+                return 0; // The board rejects the move anyway
+            }
+            board
+                .get_words(position)
+                .iter()
+                .map(|word| word.len() as u32)
+                .max()
+                .unwrap_or(1)
+        }
+        Move::Swap { .. } => 1,
+    }
 }
 
 impl Game {
+    // A two player game with the default hands and judge. This keeps its original signature rather than taking the
+    // number of players, hand size and judge, since boards only have roots for two players.
+    // Use `with_hand_size` and `with_judge` to change the others.
     pub fn new(width: usize, height: usize) -> Self {
+        Self::with_rules(width, height, Rules::default())
+    }
+
+    pub fn with_rules(width: usize, height: usize, rules: Rules) -> Self {
+        Self::from_parts(
+            Board::new(width, height).with_rules(rules),
+            Hands::default(),
+            Judge::default(),
+        )
+    }
+
+    fn from_parts(board: Board, hands: Hands, judge: Judge) -> Self {
         Self {
-            board: Board::new(width, height),
-            hands: Hands::default(),
-            judge: Judge::default(),
+            board,
+            hands,
+            judge,
             next_player: 0,
             winner: None,
+            drawn: false,
+            log: GameLog::default(),
+            events: vec![],
+            positions: HashMap::new(),
+            energy_rules: None,
+            energy: vec![],
+            peaceful_placements: 0,
+            placements: 0,
+            placement_limit: None,
+            placed: vec![],
+            phase_thresholds: PhaseThresholds::default(),
+            resigned: vec![],
+            scouted: None,
         }
     }
 
-    pub fn play_move(&mut self, next_move: Move) -> Result<Option<usize>, &str> {
-        if self.winner.is_some() {
-            return Err("Game is already over");
+    // Plays the moves in order on this unplayed game, e.g. to rebuild a recorded game.
+    // This takes the starting game rather than a board, players and hand size so that variant rules like energy,
+    // peaceful placements, placement limits and fog carry over. Build it the same way as the original,
+    // dealing the same hands, bag and all, e.g. with a seeded bag, so that every tile drawn lines up with the original game.
+    // If a move can't be played, returns its index along with the reason.
+    pub fn replay(mut self, moves: &[Move]) -> Result<Self, (usize, GamePlayError)> {
+        for (index, game_move) in moves.iter().enumerate() {
+            self.play(game_move.clone()).map_err(|err| (index, err))?;
+        }
+        Ok(self)
+    }
+
+    pub fn with_judge(mut self, judge: Judge) -> Self {
+        self.judge = judge;
+        self
+    }
+
+    // Deals every player a hand of the given size from the bag, e.g. a seeded one for a reproducible game
+    pub fn with_hand_size(mut self, hand_size: usize, bag: TileBag) -> Self {
+        self.hands = Hands::new(self.board.get_orientations().len(), hand_size, bag);
+        self
+    }
+
+    pub fn with_energy(mut self, rules: EnergyRules) -> Self {
+        self.energy = vec![rules.starting_energy; self.board.get_orientations().len()];
+        self.energy_rules = Some(rules);
+        self
+    }
+
+    // Placements don't attack until the given number of placements have been made, so that there are no skirmishes in the first few turns.
+    // This counts placements rather than tiles on the board, so tiles that are later captured or truncated still count.
+    pub fn with_peaceful_placements(mut self, placements: usize) -> Self {
+        self.peaceful_placements = placements;
+        self
+    }
+
+    // Caps how many tiles each player can place over the whole game. Swaps don't count towards the limit.
+    pub fn with_placement_limit(mut self, limit: usize) -> Self {
+        self.placement_limit = Some(limit);
+        self.placed = vec![0; self.board.get_orientations().len()];
+        self
+    }
+
+    // Gives a weaker player a bigger hand for the whole game, so they have more letters to choose from
+    pub fn with_handicap(
+        mut self,
+        player: usize,
+        extra_tiles: usize,
+    ) -> Result<Self, GamePlayError> {
+        self.hands.deal_extra(player, extra_tiles)?;
+        Ok(self)
+    }
+
+    // Hides other players' tiles from each player until they've placed a tile beside them, or on them
+    pub fn with_fog(mut self) -> Self {
+        self.scouted = Some(vec![HashSet::new(); self.board.get_orientations().len()]);
+        self.scout(None);
+        self
+    }
+
+    pub fn with_phase_thresholds(mut self, thresholds: PhaseThresholds) -> Self {
+        self.phase_thresholds = thresholds;
+        self
+    }
+
+    // Plays the move for the player whose turn it is, then checks whether it won the game and passes the turn on
+    pub fn play(&mut self, next_move: Move) -> Result<MoveResult, GamePlayError> {
+        if self.winner.is_some() || self.drawn {
+            return Err(GamePlayError::GameOver);
         }
 
         let player = match next_move {
@@ -33,28 +226,946 @@ impl Game {
             Move::Swap { player, .. } => player,
         };
         if player != self.next_player {
-            return Err("Only the next player can play");
+            return Err(GamePlayError::NotYourTurn {
+                player,
+                next: self.next_player,
+            });
         }
 
-        if let Err(msg) = self
-            .board
-            .make_move(next_move, &mut self.hands, &self.judge)
-        {
-            println!("{}", msg);
-            return Err("Couldn't make move"); // TODO: propogate error post polonius
+        if self.log.turns.is_empty() {
+            // The board and hands can be set up freely until the first move is played
+            self.log = GameLog::new(self.board.clone(), self.hands.clone());
+            self.positions = HashMap::from([(self.position_hash(), 1)]);
         }
 
+        let cost = self.check_energy(&next_move)?;
+        self.check_placement_limit(&next_move)?;
+        let result = if self.placements < self.peaceful_placements {
+            self.board
+                .make_move_peacefully(next_move.clone(), &mut self.hands, &self.judge)
+        } else {
+            self.board
+                .make_move_reporting(next_move.clone(), &mut self.hands, &self.judge)
+        };
+        let report = result?;
+        if let Some(energy) = self.energy.get_mut(player) {
+            *energy -= cost;
+        }
+        if let Move::Place { .. } = next_move {
+            self.placements += 1;
+            if let Some(placed) = self.placed.get_mut(player) {
+                *placed += 1;
+            }
+        }
+        let placement = match next_move {
+            Move::Place {
+                player, position, ..
+            } => Some((player, position)),
+            Move::Swap { .. } => None,
+        };
+        self.scout(placement);
+        self.log.record(next_move.clone(), &self.board);
+        self.events
+            .extend(SpectatorEvent::from_move(next_move, report.clone()));
+
         if let Some(winner) = Judge::winner(&(self.board)) {
             self.winner = Some(winner);
-            return Ok(Some(winner));
+            self.events.push(SpectatorEvent::Won { player: winner });
+            return Ok(MoveResult {
+                report,
+                winner: Some(winner),
+            });
+        }
+
+        self.advance_turn();
+        if let Some(rules) = &self.energy_rules {
+            self.energy[self.next_player] =
+                self.energy[self.next_player].saturating_add(rules.regeneration);
+        }
+
+        let repetitions = self.positions.entry(self.position_hash()).or_insert(0);
+        *repetitions += 1;
+        if *repetitions >= REPETITIONS_FOR_DRAW {
+            self.drawn = true;
+            self.events.push(SpectatorEvent::Drawn);
         }
 
-        self.next_player = (self.next_player + 1) % self.board.get_orientations().len(); // TODO: remove this hacky way to get the number of players
+        Ok(MoveResult {
+            report,
+            winner: None,
+        })
+    }
+
+    // Like `play`, but summarises any error for display
+    pub fn play_move(&mut self, next_move: Move) -> Result<Option<usize>, &str> {
+        self.play(next_move)
+            .map(|result| result.winner)
+            .map_err(|err| match err {
+                GamePlayError::GameOver => "Game is already over",
+                GamePlayError::NotYourTurn { .. } => "Only the next player can play",
+                GamePlayError::InsufficientEnergy { .. } => "Not enough energy",
+                GamePlayError::PlacementLimitReached { .. } => "Placement limit reached",
+                _ => "Couldn't make move",
+            })
+    }
+
+    // Takes the player out of the game. Once only one player is left they win, otherwise everyone else plays on without them.
+    pub fn resign(&mut self, player: usize) -> Result<Option<usize>, &str> {
+        if self.winner.is_some() || self.drawn {
+            return Err("Game is already over");
+        }
+        let players = self.board.get_orientations().len(); // TODO: remove this hacky way to get the number of players
+        if player >= players {
+            return Err("Player does not exist");
+        }
+        if self.resigned.contains(&player) {
+            return Err("Player has already resigned");
+        }
 
+        self.resigned.push(player);
+        self.events.push(SpectatorEvent::Resigned { player });
+        let remaining: Vec<usize> = (0..players)
+            .filter(|p| !self.resigned.contains(p))
+            .collect();
+        if let [winner] = remaining[..] {
+            self.winner = Some(winner);
+            self.events.push(SpectatorEvent::Won { player: winner });
+            return Ok(Some(winner));
+        }
+        if player == self.next_player {
+            self.advance_turn();
+        }
         Ok(None)
     }
 
+    // What the player can see of the board, which is everything unless the game is played with fog of war
+    pub fn view(&self, player: usize) -> GameView {
+        let scouted = self
+            .scouted
+            .as_ref()
+            .map(|scouted| scouted.get(player).cloned().unwrap_or_default());
+        GameView::new(&self.board, player, scouted.as_ref())
+    }
+
+    // Players see their root, their tiles, and every square beside them. Once seen, a square stays seen.
+    // They also see around a tile they've just placed, even if it was lost in the battle it started.
+    fn scout(&mut self, placement: Option<(usize, Coordinate)>) {
+        let scouted = match &mut self.scouted {
+            Some(scouted) => scouted,
+            None => return,
+        };
+        let board = &self.board;
+        let lookouts = (0..scouted.len())
+            .flat_map(|player| {
+                board
+                    .tiles_of(player)
+                    .into_iter()
+                    .map(move |(position, _)| (player, position))
+                    .chain(board.get_root(player).map(|root| (player, root)))
+            })
+            .chain(placement);
+        for (player, position) in lookouts {
+            let seen = &mut scouted[player];
+            seen.insert(position);
+            seen.extend(
+                board
+                    .neighbouring_squares(position)
+                    .into_iter()
+                    .map(|(neighbour, _)| neighbour),
+            );
+        }
+    }
+
+    // Passes the turn to the next player who hasn't resigned
+    fn advance_turn(&mut self) {
+        let players = self.board.get_orientations().len(); // TODO: remove this hacky way to get the number of players
+        loop {
+            self.next_player = (self.next_player + 1) % players;
+            if !self.resigned.contains(&self.next_player) {
+                break;
+            }
+        }
+    }
+
+    pub fn winner(&self) -> Option<usize> {
+        self.winner
+    }
+
+    // What the move would cost under the energy rules, or an error if the player can't afford it. Moves are free without energy rules.
+    pub fn check_energy(&self, game_move: &Move) -> Result<u32, GamePlayError> {
+        let rules = match &self.energy_rules {
+            Some(rules) => rules,
+            None => return Ok(0),
+        };
+        let player = match *game_move {
+            Move::Place { player, .. } => player,
+            Move::Swap { player, .. } => player,
+        };
+        let available = *self
+            .energy
+            .get(player)
+            .ok_or(GamePlayError::NonExistentPlayer { index: player })?;
+        let cost = (rules.cost)(&self.board, game_move);
+        if cost > available {
+            return Err(GamePlayError::InsufficientEnergy {
+                player,
+                cost,
+                available,
+            });
+        }
+        Ok(cost)
+    }
+
+    // Whether the player still has placements left under the placement limit. Swaps are always allowed.
+    pub fn check_placement_limit(&self, game_move: &Move) -> Result<(), GamePlayError> {
+        let (limit, player) = match (self.placement_limit, game_move) {
+            (Some(limit), Move::Place { player, .. }) => (limit, *player),
+            _ => return Ok(()),
+        };
+        let placed = *self
+            .placed
+            .get(player)
+            .ok_or(GamePlayError::NonExistentPlayer { index: player })?;
+        if placed >= limit {
+            return Err(GamePlayError::PlacementLimitReached { player, limit });
+        }
+        Ok(())
+    }
+
+    // Each player's remaining energy, which is empty unless the game is played with energy rules
+    pub fn energy(&self) -> &[u32] {
+        &self.energy
+    }
+
+    // The game is in its endgame once the board is full enough or the bag is nearly empty, and its midgame once some tiles are down
+    pub fn phase(&self) -> Phase {
+        let squares: Vec<Square> = (0..self.board.height())
+            .flat_map(|y| (0..self.board.width()).map(move |x| Coordinate { x, y }))
+            .filter_map(|position| self.board.get(position).ok())
+            .collect();
+        let tiles = squares
+            .iter()
+            .filter(|square| **square != Square::Empty)
+            .count();
+        let fill = tiles as f32 / squares.len().max(1) as f32;
+        let bag = self.hands.bag();
+        let bag_left = bag.len() as f32 / bag.refill_size().max(1) as f32;
+
+        let thresholds = &self.phase_thresholds;
+        if fill >= thresholds.endgame_fill || bag_left <= thresholds.endgame_bag {
+            Phase::Endgame
+        } else if fill >= thresholds.midgame_fill {
+            Phase::Midgame
+        } else {
+            Phase::Opening
+        }
+    }
+
+    // Whether the game ended without a winner, because the same position came up too many times
+    pub fn is_drawn(&self) -> bool {
+        self.drawn
+    }
+
+    // Identifies the whole position: the board, what each player holds, and whose turn it is
+    fn position_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.board.canonical_hash().hash(&mut hasher);
+        self.hands.hash(&mut hasher);
+        self.energy.hash(&mut hasher);
+        self.next_player.hash(&mut hasher);
+        hasher.finish()
+    }
+
     pub fn next(&self) -> usize {
         self.next_player
     }
+
+    pub fn log(&self) -> &GameLog {
+        &self.log
+    }
+
+    // Everything that has happened so far, including hidden information like the tiles lost in failed attacks
+    pub fn spectator_events(&self) -> &[SpectatorEvent] {
+        &self.events
+    }
+
+    // A rough guess at how many more turns the game will last, assuming the player closest to their goal heads straight for it.
+    // The bag refills itself when it runs dry so it never limits the game, but the number of empty squares does.
+    pub fn estimated_remaining_moves(&self) -> usize {
+        let players = self.board.get_orientations().len();
+        let empty_squares = (0..self.board.height())
+            .flat_map(|y| (0..self.board.width()).map(move |x| Coordinate { x, y }))
+            .filter(|&position| self.board.get(position) == Ok(Square::Empty))
+            .count();
+
+        (0..players)
+            .filter_map(|player| self.board.distance_to_goal(player))
+            .min()
+            .map_or(empty_squares, |distance| {
+                usize::min(distance * players, empty_squares)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::bag::{tests as TileUtils, TileBag};
+    use super::*;
+    use crate::board::{tests as BoardUtils, Direction};
+    use crate::judge::Outcome;
+    use crate::view::SeenSquare;
+
+    fn game_from(board: Board) -> Game {
+        Game {
+            board,
+            hands: Hands::new(2, 7, TileUtils::trivial_bag()),
+            judge: Judge::new(vec!["BIG", "FAT", "JOLLY", "AND", "SILLY", "FOLK", "ARTS"]),
+            next_player: 0,
+            winner: None,
+            drawn: false,
+            log: GameLog::default(),
+            events: vec![],
+            positions: HashMap::new(),
+            energy_rules: None,
+            energy: vec![],
+            peaceful_placements: 0,
+            placements: 0,
+            placement_limit: None,
+            placed: vec![],
+            phase_thresholds: PhaseThresholds::default(),
+            resigned: vec![],
+            scouted: None,
+        }
+    }
+
+    fn almost_won() -> Board {
+        // Player 0 is one tile away from their goal, but the tile would form an invalid attacking word
+        BoardUtils::from_string(
+            [
+                "_ _ X _ _",
+                "_ _ X _ _",
+                "_ _ X _ _",
+                "_ _ _ _ _",
+                "_ _ B _ _",
+            ]
+            .join("\n"),
+            vec![Coordinate { x: 2, y: 0 }, Coordinate { x: 2, y: 4 }],
+            vec![Direction::North, Direction::South],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn estimated_remaining_moves() {
+        let opening = game_from(Board::new(5, 5));
+        assert_eq!(opening.estimated_remaining_moves(), 12);
+
+        let endgame = game_from(almost_won());
+        assert_eq!(endgame.estimated_remaining_moves(), 2);
+    }
+
+    #[test]
+    fn phase() {
+        let mut dist = [0; 26];
+        dist[0] = 20;
+        let mut game = game_from(Board::new(3, 3)).with_phase_thresholds(PhaseThresholds {
+            midgame_fill: 0.2,
+            endgame_fill: 0.5,
+            endgame_bag: 0.25,
+        });
+        game.hands = Hands::new(2, 2, TileBag::new(dist)); // 16 of the 20 tiles are left in the bag
+        assert_eq!(game.phase(), Phase::Opening);
+
+        // There are 11 squares, counting the roots
+        let column: Vec<Coordinate> = (0..=3).map(|y| Coordinate { x: 1, y }).collect();
+        for &position in &column[..3] {
+            game.board.set(position, 0, 'A').unwrap();
+        }
+        assert_eq!(game.phase(), Phase::Midgame);
+        game.board.set(column[3], 0, 'A').unwrap();
+        game.board.set(Coordinate { x: 0, y: 1 }, 0, 'A').unwrap();
+        game.board.set(Coordinate { x: 2, y: 1 }, 0, 'A').unwrap();
+        assert_eq!(game.phase(), Phase::Endgame);
+
+        // Running low on tiles also ends the midgame
+        game.board.clear(Coordinate { x: 0, y: 1 });
+        game.board.clear(Coordinate { x: 2, y: 1 });
+        game.board.clear(column[3]);
+        assert_eq!(game.phase(), Phase::Midgame);
+        for _ in 0..10 {
+            game.hands.use_tile(1, 'A').unwrap();
+        }
+        assert_eq!(game.hands.bag().len(), 6);
+        assert_eq!(game.phase(), Phase::Midgame);
+        game.hands.use_tile(1, 'A').unwrap();
+        assert_eq!(game.phase(), Phase::Endgame);
+    }
+
+    #[test]
+    fn draw_by_repetition() {
+        let mut game = game_from(
+            BoardUtils::from_string(
+                [
+                    "_ _ A _ _",
+                    "_ _ B _ _",
+                    "_ _ E _ _",
+                    "_ _ _ _ _",
+                    "_ _ F _ _",
+                    "_ _ C _ _",
+                    "_ _ D _ _",
+                ]
+                .join("\n"),
+                vec![Coordinate { x: 2, y: 0 }, Coordinate { x: 2, y: 6 }],
+                vec![Direction::North, Direction::South],
+            )
+            .unwrap(),
+        );
+        let swap = |player, y| Move::Swap {
+            player,
+            positions: [Coordinate { x: 2, y }, Coordinate { x: 2, y: y + 1 }],
+        };
+        let round = [swap(0, 1), swap(1, 4), swap(0, 1), swap(1, 4)];
+
+        // Each round of swapping and swapping back returns to the starting position
+        for played in round.iter().chain(round[..3].iter()) {
+            assert_eq!(game.play_move(played.clone()), Ok(None));
+            assert!(!game.is_drawn());
+        }
+        assert_eq!(game.play_move(round[3].clone()), Ok(None));
+        assert!(game.is_drawn());
+        assert_eq!(
+            game.play_move(round[0].clone()),
+            Err("Game is already over")
+        );
+    }
+
+    #[test]
+    fn spectator_events() {
+        let mut game = game_from(
+            BoardUtils::from_string(
+                [
+                    "_ S X _ _",
+                    "_ T _ _ _",
+                    "_ R _ _ _",
+                    "_ _ I _ _",
+                    "_ _ T _ _",
+                    "_ _ E _ _",
+                ]
+                .join("\n"),
+                vec![Coordinate { x: 2, y: 0 }, Coordinate { x: 2, y: 5 }],
+                vec![Direction::North, Direction::South],
+            )
+            .unwrap(),
+        );
+        let attack = Move::Place {
+            player: 0,
+            tile: 'A',
+            position: Coordinate { x: 1, y: 3 },
+        };
+        let rebuild = Move::Place {
+            player: 1,
+            tile: 'A',
+            position: Coordinate { x: 2, y: 5 },
+        };
+        game.play_move(attack.clone()).unwrap();
+        game.play_move(rebuild.clone()).unwrap();
+
+        assert_eq!(
+            game.spectator_events(),
+            [
+                SpectatorEvent::Moved { played: attack },
+                SpectatorEvent::Battle {
+                    outcome: Outcome::AttackerWins(vec![0])
+                },
+                SpectatorEvent::Captured {
+                    squares: vec![
+                        Coordinate { x: 2, y: 3 },
+                        Coordinate { x: 2, y: 4 },
+                        Coordinate { x: 2, y: 5 }
+                    ]
+                },
+                SpectatorEvent::Moved { played: rebuild },
+            ]
+        );
+    }
+
+    #[test]
+    fn peaceful_opening() {
+        let board = BoardUtils::from_string(
+            [
+                "_ S X _ _",
+                "_ T _ _ _",
+                "_ R _ _ _",
+                "_ _ I _ _",
+                "_ _ T _ _",
+                "_ _ E _ _",
+            ]
+            .join("\n"),
+            vec![Coordinate { x: 2, y: 0 }, Coordinate { x: 2, y: 5 }],
+            vec![Direction::North, Direction::South],
+        )
+        .unwrap();
+        let place = |player, x, y| Move::Place {
+            player,
+            tile: 'A',
+            position: Coordinate { x, y },
+        };
+        let battles = |game: &Game| {
+            game.spectator_events()
+                .iter()
+                .filter(|event| matches!(event, SpectatorEvent::Battle { .. }))
+                .count()
+        };
+
+        // Normally completing ARTS beside ITE captures it
+        let mut game = game_from(board.clone());
+        game.play_move(place(0, 1, 3)).unwrap();
+        assert_eq!(battles(&game), 1);
+        assert_eq!(game.board.get(Coordinate { x: 2, y: 3 }), Ok(Square::Empty));
+
+        let mut game = game_from(board).with_peaceful_placements(2);
+        game.judge = Judge::new(vec!["ARTS", "AT"]);
+        game.play_move(place(0, 1, 3)).unwrap();
+        assert_eq!(battles(&game), 0);
+        assert_eq!(
+            game.board.get(Coordinate { x: 2, y: 3 }),
+            Ok(Square::Occupied(1, 'I', false))
+        );
+        game.play_move(place(1, 3, 5)).unwrap();
+
+        // Once two placements have been made, placements attack again
+        game.play_move(place(0, 0, 2)).unwrap();
+        assert_eq!(battles(&game), 0); // There was nobody beside this one
+        game.play_move(place(1, 1, 4)).unwrap(); // AT attacks ARTS and loses
+        assert_eq!(battles(&game), 1);
+        assert_eq!(game.board.get(Coordinate { x: 1, y: 4 }), Ok(Square::Empty));
+    }
+
+    #[test]
+    fn replay_keeps_variant_rules() {
+        let board = BoardUtils::from_string(
+            [
+                "_ S X _ _",
+                "_ T _ _ _",
+                "_ R _ _ _",
+                "_ _ I _ _",
+                "_ _ T _ _",
+                "_ _ E _ _",
+            ]
+            .join("\n"),
+            vec![Coordinate { x: 2, y: 0 }, Coordinate { x: 2, y: 5 }],
+            vec![Direction::North, Direction::South],
+        )
+        .unwrap();
+        let start = || game_from(board.clone()).with_peaceful_placements(2);
+        let moves = [Move::Place {
+            player: 0,
+            tile: 'A',
+            position: Coordinate { x: 1, y: 3 },
+        }];
+
+        // The placement would capture ITE, but it's peaceful
+        let mut game = start();
+        game.play_move(moves[0].clone()).unwrap();
+        let replayed = start().replay(&moves).unwrap();
+        assert_eq!(replayed.board.to_string(), game.board.to_string());
+        assert_eq!(
+            replayed.board.get(Coordinate { x: 2, y: 3 }),
+            Ok(Square::Occupied(1, 'I', false))
+        );
+        assert_eq!(replayed.spectator_events(), game.spectator_events());
+
+        // Without the variant rules the same moves lead somewhere else
+        let unconfigured = game_from(board.clone()).replay(&moves).unwrap();
+        assert_ne!(unconfigured.board.to_string(), game.board.to_string());
+    }
+
+    #[test]
+    fn resign() {
+        let mut game = game_from(Board::new(5, 5));
+        let place = |player, y| Move::Place {
+            player,
+            tile: 'A',
+            position: Coordinate { x: 2, y },
+        };
+        game.play_move(place(0, 0)).unwrap();
+
+        assert_eq!(game.resign(2), Err("Player does not exist"));
+        assert_eq!(game.resign(0), Ok(Some(1)));
+        assert_eq!(game.winner(), Some(1));
+        assert_eq!(
+            game.spectator_events()[1..],
+            [
+                SpectatorEvent::Resigned { player: 0 },
+                SpectatorEvent::Won { player: 1 }
+            ]
+        );
+        assert_eq!(game.play_move(place(1, 6)), Err("Game is already over"));
+        assert_eq!(game.resign(1), Err("Game is already over"));
+    }
+
+    #[test]
+    fn resign_with_more_players() {
+        let board = BoardUtils::from_string(
+            ["_ _ _", "_ _ _", "_ _ _"].join("\n"),
+            vec![
+                Coordinate { x: 0, y: 0 },
+                Coordinate { x: 2, y: 0 },
+                Coordinate { x: 1, y: 2 },
+            ],
+            vec![Direction::North, Direction::North, Direction::South],
+        )
+        .unwrap();
+        let mut game = game_from(board);
+        game.hands = Hands::new(3, 7, TileUtils::trivial_bag());
+
+        // The other two players play on, skipping the resigned player's turns
+        assert_eq!(game.resign(0), Ok(None));
+        assert_eq!(game.next(), 1);
+        game.play_move(Move::Place {
+            player: 1,
+            tile: 'A',
+            position: Coordinate { x: 2, y: 0 },
+        })
+        .unwrap();
+        assert_eq!(game.next(), 2);
+        assert_eq!(game.resign(0), Err("Player has already resigned"));
+        assert_eq!(game.resign(1), Ok(Some(2)));
+    }
+
+    #[test]
+    fn fog() {
+        let board = BoardUtils::from_string(
+            [
+                "_ _ A _ _",
+                "_ _ _ _ _",
+                "_ _ _ _ _",
+                "_ _ _ B _",
+                "_ _ _ C _",
+                "_ _ _ D _",
+            ]
+            .join("\n"),
+            vec![Coordinate { x: 2, y: 0 }, Coordinate { x: 3, y: 5 }],
+            vec![Direction::North, Direction::South],
+        )
+        .unwrap();
+        let mut game = game_from(board).with_fog();
+        let near = Coordinate { x: 3, y: 3 };
+        let far = Coordinate { x: 3, y: 5 };
+
+        // Each player only sees their own side of the board
+        let view = game.view(0);
+        assert_eq!(
+            view.get(Coordinate { x: 2, y: 0 }),
+            Some(SeenSquare::Seen(Square::Occupied(0, 'A', false)))
+        );
+        assert_eq!(view.get(near), Some(SeenSquare::Hidden));
+        assert_eq!(view.get(far), Some(SeenSquare::Hidden));
+        assert_eq!(
+            view.get(Coordinate { x: 2, y: 3 }),
+            Some(SeenSquare::Seen(Square::Empty))
+        );
+        assert_eq!(
+            game.view(1).get(near),
+            Some(SeenSquare::Seen(Square::Occupied(1, 'B', false)))
+        );
+
+        // Attacking an enemy tile reveals it, even though the attack fails, but not the tiles further away
+        for y in 1..=3 {
+            game.play_move(Move::Place {
+                player: 0,
+                tile: 'A',
+                position: Coordinate { x: 2, y },
+            })
+            .unwrap();
+            game.play_move(Move::Swap {
+                player: 1,
+                positions: [near, Coordinate { x: 3, y: 4 }],
+            })
+            .unwrap();
+        }
+        let view = game.view(0);
+        assert!(matches!(
+            view.get(near),
+            Some(SeenSquare::Seen(Square::Occupied(1, _, _)))
+        ));
+        assert_eq!(view.get(far), Some(SeenSquare::Hidden));
+
+        // Without fog everything is seen
+        let game = game_from(almost_won());
+        assert_eq!(
+            game.view(0).get(Coordinate { x: 2, y: 4 }),
+            Some(SeenSquare::Seen(Square::Occupied(1, 'B', false)))
+        );
+        assert_eq!(game.view(0).get(Coordinate { x: 9, y: 9 }), None);
+    }
+
+    #[test]
+    fn winning_move_can_skip_combat() {
+        let winning_move = Move::Place {
+            player: 0,
+            tile: 'A',
+            position: Coordinate { x: 2, y: 3 },
+        };
+
+        // Normally the attack fails and the attacking tiles are cleared before the win is checked
+        let mut game = game_from(almost_won());
+        assert_eq!(game.play_move(winning_move.clone()), Ok(None));
+        assert_eq!(game.board.get(Coordinate { x: 2, y: 3 }), Ok(Square::Empty));
+
+        let mut game = game_from(almost_won().with_rules(Rules {
+            win_skips_combat: true,
+            ..Rules::default()
+        }));
+        assert_eq!(game.play_move(winning_move), Ok(Some(0)));
+        assert_eq!(
+            game.board.get(Coordinate { x: 2, y: 4 }),
+            Ok(Square::Occupied(1, 'B', false))
+        );
+    }
+
+    #[test]
+    fn play() {
+        let mut game = game_from(almost_won().with_rules(Rules {
+            win_skips_combat: true,
+            ..Rules::default()
+        }));
+        let winning_move = Move::Place {
+            player: 0,
+            tile: 'A',
+            position: Coordinate { x: 2, y: 3 },
+        };
+
+        // Only the current player can move
+        assert_eq!(
+            game.play(Move::Place {
+                player: 1,
+                tile: 'A',
+                position: Coordinate { x: 1, y: 4 },
+            }),
+            Err(GamePlayError::NotYourTurn { player: 1, next: 0 })
+        );
+        assert_eq!(
+            game.play(Move::Place {
+                player: 0,
+                tile: 'A',
+                position: Coordinate { x: 0, y: 4 },
+            }),
+            Err(GamePlayError::NonAdjacentPlace)
+        );
+        assert_eq!(game.next(), 0);
+
+        assert_eq!(
+            game.play(winning_move.clone()),
+            Ok(MoveResult {
+                report: MoveReport::default(),
+                winner: Some(0),
+            })
+        );
+        assert_eq!(game.winner(), Some(0));
+        assert_eq!(game.play(winning_move), Err(GamePlayError::GameOver));
+    }
+
+    #[test]
+    fn with_hand_size() {
+        let mut dist = [0; 26];
+        dist[0] = 10; // Only As
+        let game = Game::new(3, 3).with_hand_size(4, TileBag::new(dist));
+        assert_eq!(game.hands.get_hand(0), &vec!['A'; 4]);
+        assert_eq!(game.hands.get_hand(1), &vec!['A'; 4]);
+    }
+
+    #[test]
+    fn replay() {
+        let start = || {
+            Game::new(3, 3)
+                .with_judge(Judge::new(vec![
+                    "BIG", "FAT", "JOLLY", "AND", "SILLY", "FOLK", "ARTS",
+                ]))
+                .with_hand_size(7, TileBag::default().with_seed(11))
+        };
+        let mut game = start();
+        for (player, x, y) in [
+            (0, 1, 0),
+            (1, 1, 4),
+            (0, 1, 1),
+            (1, 1, 3),
+            (0, 0, 1),
+            (1, 0, 3),
+        ] {
+            let tile = game.hands.get_hand(player)[0];
+            game.play(Move::Place {
+                player,
+                tile,
+                position: Coordinate { x, y },
+            })
+            .unwrap();
+        }
+        game.play(Move::Swap {
+            player: 0,
+            positions: [Coordinate { x: 0, y: 1 }, Coordinate { x: 1, y: 1 }],
+        })
+        .unwrap();
+        let moves: Vec<Move> = game
+            .log()
+            .turns
+            .iter()
+            .map(|turn| turn.played.clone())
+            .collect();
+
+        let replayed = start().replay(&moves).unwrap();
+        assert_eq!(replayed.board.to_string(), game.board.to_string());
+        assert_eq!(replayed.hands, game.hands);
+        assert_eq!(replayed.next(), game.next());
+
+        // A corrupt log reports the first move that couldn't be played
+        let mut corrupt = moves.clone();
+        corrupt.push(Move::Place {
+            player: 1,
+            tile: game.hands.get_hand(1)[0],
+            position: Coordinate { x: 1, y: 3 },
+        });
+        assert_eq!(
+            start().replay(&corrupt).err(),
+            Some((7, GamePlayError::OccupiedPlace))
+        );
+        corrupt.swap(0, 1);
+        assert_eq!(
+            start().replay(&corrupt).err(),
+            Some((0, GamePlayError::NotYourTurn { player: 1, next: 0 }))
+        );
+    }
+
+    #[test]
+    fn energy() {
+        let mut game = game_from(Board::new(5, 5)).with_energy(EnergyRules {
+            starting_energy: 4,
+            regeneration: 1,
+            cost: |_, game_move| match game_move {
+                Move::Place { .. } => 2,
+                Move::Swap { .. } => 0,
+            },
+        });
+        let place = |player, y| Move::Place {
+            player,
+            tile: 'A',
+            position: Coordinate { x: 2, y },
+        };
+        let swap = |player, y| Move::Swap {
+            player,
+            positions: [Coordinate { x: 2, y }, Coordinate { x: 2, y: y + 1 }],
+        };
+
+        for played in [
+            place(0, 0),
+            place(1, 6),
+            place(0, 1),
+            place(1, 5),
+            place(0, 2),
+            place(1, 4),
+        ] {
+            assert_eq!(game.play_move(played), Ok(None));
+        }
+        assert_eq!(game.energy(), [1, 1]);
+
+        // Player 0 has spent more than they've regained
+        let next_place = Move::Place {
+            player: 0,
+            tile: 'A',
+            position: Coordinate { x: 1, y: 2 },
+        };
+        assert_eq!(
+            game.check_energy(&next_place),
+            Err(GamePlayError::InsufficientEnergy {
+                player: 0,
+                cost: 2,
+                available: 1
+            })
+        );
+        assert_eq!(game.play_move(next_place.clone()), Err("Not enough energy"));
+        assert_eq!(game.next(), 0);
+
+        // A free move passes the turn, and they regain enough energy to place again when it comes back around
+        assert_eq!(game.play_move(swap(0, 1)), Ok(None));
+        assert_eq!(game.play_move(swap(1, 4)), Ok(None));
+        assert_eq!(game.energy(), [2, 2]);
+        assert_eq!(game.play_move(next_place), Ok(None));
+        assert_eq!(game.energy(), [0, 3]);
+    }
+
+    #[test]
+    fn placement_limit() {
+        let mut game = game_from(Board::new(5, 5)).with_placement_limit(3);
+        let place = |player, y| Move::Place {
+            player,
+            tile: 'A',
+            position: Coordinate { x: 2, y },
+        };
+        let swap = |player, y| Move::Swap {
+            player,
+            positions: [Coordinate { x: 2, y }, Coordinate { x: 2, y: y + 1 }],
+        };
+
+        for played in [
+            place(0, 0),
+            place(1, 6),
+            place(0, 1),
+            place(1, 5),
+            place(0, 2),
+            place(1, 4),
+        ] {
+            assert_eq!(game.play_move(played), Ok(None));
+        }
+
+        // Player 0 has used up their placements, but can still swap
+        assert_eq!(
+            game.check_placement_limit(&place(0, 3)),
+            Err(GamePlayError::PlacementLimitReached {
+                player: 0,
+                limit: 3
+            })
+        );
+        assert_eq!(game.play_move(place(0, 3)), Err("Placement limit reached"));
+        assert_eq!(game.next(), 0);
+        assert_eq!(game.play_move(swap(0, 1)), Ok(None));
+        assert_eq!(game.next(), 1);
+    }
+
+    #[test]
+    fn handicap() {
+        let mut game = game_from(Board::new(5, 5)).with_handicap(1, 2).unwrap();
+        assert_eq!(game.hands.get_hand(0).len(), 7);
+        assert_eq!(game.hands.get_hand(1).len(), 9);
+
+        // The extra tiles are replaced like any other, so the hand stays bigger
+        for (player, y) in [(0, 0), (1, 6), (0, 1), (1, 5)] {
+            let placement = Move::Place {
+                player,
+                tile: 'A',
+                position: Coordinate { x: 2, y },
+            };
+            assert_eq!(game.play_move(placement), Ok(None));
+        }
+        assert_eq!(game.hands.get_hand(0).len(), 7);
+        assert_eq!(game.hands.get_hand(1).len(), 9);
+
+        assert!(matches!(
+            game_from(Board::new(5, 5)).with_handicap(2, 1),
+            Err(GamePlayError::NonExistentPlayer { index: 2 })
+        ));
+    }
+
+    #[test]
+    fn word_length_cost() {
+        let mut b = Board::new(5, 5);
+        let place = |y| Move::Place {
+            player: 0,
+            tile: 'A',
+            position: Coordinate { x: 2, y },
+        };
+        assert_eq!(super::word_length_cost(&b, &place(0)), 1);
+        b.set(Coordinate { x: 2, y: 0 }, 0, 'A').unwrap();
+        b.set(Coordinate { x: 2, y: 1 }, 0, 'A').unwrap();
+        assert_eq!(super::word_length_cost(&b, &place(2)), 3);
+    }
 }
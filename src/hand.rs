@@ -1,31 +1,96 @@
+use std::hash::{Hash, Hasher};
+
 use crate::error::GamePlayError;
 
-use super::bag::TileBag;
+use super::bag::{tile_of, TileBag};
+use super::board::Board;
+use super::judge::Judge;
+use super::moves::Move;
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug)]
 pub struct Hands {
     hands: Vec<Vec<char>>,
     bag: TileBag,
+    max_duplicate_letters: Option<usize>, // The most copies of a single letter that a hand can hold
+    capacities: Vec<usize>, // How many tiles each player's hand holds, including any extra tiles they were dealt
+    last_drawn: Vec<Option<usize>>, // Where each player's latest replacement tile is in their hand, so that a used tile can be taken back
 }
 
 impl Hands {
-    pub fn new(player_count: usize, capacity: usize, mut bag: TileBag) -> Self {
-        let mut hands = Vec::new();
+    pub fn new(player_count: usize, capacity: usize, bag: TileBag) -> Self {
+        Self::deal(player_count, capacity, bag, None)
+    }
+
+    // Deals hands from a seeded bag, so that games created with the same seed draw identical tiles in the same order
+    pub fn new_seeded(player_count: usize, capacity: usize, bag: TileBag, seed: u64) -> Self {
+        Self::new(player_count, capacity, bag.with_seed(seed))
+    }
+
+    // Deals hands that never hold more than `max_duplicate_letters` of the same letter, redrawing any excess duplicates
+    pub fn with_duplicate_limit(
+        player_count: usize,
+        capacity: usize,
+        bag: TileBag,
+        max_duplicate_letters: usize,
+    ) -> Self {
+        Self::deal(player_count, capacity, bag, Some(max_duplicate_letters))
+    }
+
+    fn deal(
+        player_count: usize,
+        capacity: usize,
+        bag: TileBag,
+        max_duplicate_letters: Option<usize>,
+    ) -> Self {
+        let mut hands = Self {
+            hands: vec![Vec::with_capacity(capacity); player_count],
+            bag,
+            max_duplicate_letters,
+            capacities: vec![capacity; player_count],
+            last_drawn: vec![None; player_count],
+        };
         for player in 0..player_count {
-            hands.push(Vec::with_capacity(capacity));
             for _ in 0..capacity {
-                hands[player].push(bag.draw_tile());
+                let tile = hands.draw_for(player);
+                hands.hands[player].push(tile);
             }
         }
-        Self { hands, bag }
+        hands
+    }
+
+    // Deals the player extra tiles on top of their hand. Used tiles are always replaced, so their hand stays bigger for the rest of the game.
+    pub fn deal_extra(&mut self, player: usize, count: usize) -> Result<(), GamePlayError> {
+        if player >= self.hands.len() {
+            return Err(GamePlayError::NonExistentPlayer { index: player });
+        }
+        for _ in 0..count {
+            let tile = self.draw_for(player);
+            self.hands[player].push(tile);
+        }
+        self.capacities[player] += count;
+        Ok(())
+    }
+
+    // Checks that the player has the tile, without using it. Lowercase letters need a blank.
+    pub fn check_tile(&self, player: usize, tile: char) -> Result<(), GamePlayError> {
+        match self.hands.get(player) {
+            None => Err(GamePlayError::NonExistentPlayer { index: player }),
+            Some(hand) if !hand.contains(&tile_of(tile)) => {
+                Err(GamePlayError::PlayerDoesNotHaveTile { player, tile })
+            }
+            _ => Ok(()),
+        }
     }
 
     pub fn use_tile(&mut self, player: usize, tile: char) -> Result<(), GamePlayError> {
         if let Some(hand) = self.hands.get_mut(player) {
-            match hand.iter().position(|t| t == &tile) {
+            match hand.iter().position(|&t| t == tile_of(tile)) {
                 None => Err(GamePlayError::PlayerDoesNotHaveTile { player, tile }),
                 Some(index) => {
-                    hand[index] = self.bag.draw_tile();
+                    hand.remove(index);
+                    let replacement = self.draw_for(player);
+                    self.hands[player].insert(index, replacement);
+                    self.last_drawn[player] = Some(index);
                     Ok(())
                 }
             }
@@ -34,12 +99,135 @@ impl Hands {
         }
     }
 
+    // Draws a tile for the player's hand, setting aside tiles that would break the duplicate limit.
+    // If the bag can't provide an acceptable tile, even after refilling, the player gets the duplicate anyway.
+    fn draw_for(&mut self, player: usize) -> char {
+        let limit = match self.max_duplicate_letters {
+            Some(limit) => limit,
+            None => return self.bag.draw_tile(),
+        };
+
+        let attempts = self.bag.len() + self.bag.refill_size();
+        let mut set_aside = Vec::new();
+        let mut tile = self.bag.draw_tile();
+        while self.hands[player].iter().filter(|&&t| t == tile).count() >= limit
+            && set_aside.len() < attempts
+        {
+            set_aside.push(tile);
+            tile = self.bag.draw_tile();
+        }
+        for excess in set_aside {
+            self.bag.return_tile(excess);
+        }
+        tile
+    }
+
     pub fn get_hand(&self, player: usize) -> &Vec<char> {
         &self.hands[player]
     }
 
+    pub fn bag(&self) -> &TileBag {
+        &self.bag
+    }
+
+    // Changes the order that tiles will be drawn from the bag, e.g. so that simulated games don't all draw the same tiles
+    pub fn reseed(&mut self, seed: u64) {
+        self.bag.reseed(seed);
+    }
+
+    // Returns a tile to the bag. As in a move, a lowercase letter stands for a blank, which goes back as a blank.
     pub fn return_tile(&mut self, c: char) {
-        self.bag.return_tile(c);
+        self.bag.return_tile(tile_of(c));
+    }
+
+    // Puts a used tile back in the player's hand in place of the tile they drew to replace it, which goes back in the bag.
+    // If they haven't drawn since, the tile is added to their hand, so long as it isn't already full.
+    // Returns the tile that went back in the bag, if any.
+    pub fn take_back(&mut self, player: usize, tile: char) -> Result<Option<char>, GamePlayError> {
+        let hand = self
+            .hands
+            .get_mut(player)
+            .ok_or(GamePlayError::NonExistentPlayer { index: player })?;
+        match self.last_drawn[player].take() {
+            Some(index) => {
+                let replacement = std::mem::replace(&mut hand[index], tile_of(tile));
+                self.bag.return_tile(replacement);
+                Ok(Some(replacement))
+            }
+            None if hand.len() >= self.capacities[player] => {
+                Err(GamePlayError::HandFull { player })
+            }
+            None => {
+                hand.push(tile_of(tile)); // Nothing has been drawn since the last take back
+                Ok(None)
+            }
+        }
+    }
+
+    // Scores each distinct tile in the player's hand by the longest word it could form with a single placement, as a hint for which tile to play.
+    // Every word the placement forms has to be valid for it to count, so tiles that can't make a valid word anywhere score zero. The best tiles come first.
+    pub fn rank_tiles(&self, player: usize, board: &Board, judge: &Judge) -> Vec<(char, f32)> {
+        let mut ranked: Vec<(char, f32)> = Vec::new();
+        for tile in &self.hands[player] {
+            if !ranked.iter().any(|(ranked_tile, _)| ranked_tile == tile) {
+                ranked.push((*tile, 0.0));
+            }
+        }
+
+        for placement in board.legal_placements(player, self) {
+            if let Move::Place { tile, position, .. } = placement {
+                let mut board = board.clone();
+                board
+                    .set(position, player, tile)
+                    .expect("Legal placements are on empty squares");
+                let words = board
+                    .word_strings(&board.get_words(position))
+                    .expect("Words were just found and should be valid");
+                if !words.iter().all(|word| judge.valid(word)) {
+                    continue;
+                }
+                let longest = words.iter().map(|word| word.len()).max().unwrap_or(0) as f32;
+                // Blanks are placed as lowercase letters, but ranked as the blank tile in the hand
+                for (ranked_tile, score) in ranked.iter_mut() {
+                    if *ranked_tile == tile_of(tile) && longest > *score {
+                        *score = longest;
+                    }
+                }
+            }
+        }
+
+        ranked.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+        ranked
+    }
+
+    // Each hand's tiles in a fixed order, since the order tiles sit in a hand doesn't matter
+    fn racks(&self) -> Vec<Vec<char>> {
+        self.hands
+            .iter()
+            .map(|hand| {
+                let mut rack = hand.clone();
+                rack.sort_unstable();
+                rack
+            })
+            .collect()
+    }
+}
+
+// Which tile was drawn last, and how big each hand is, are bookkeeping rather than part of the hands' state
+impl PartialEq for Hands {
+    fn eq(&self, other: &Self) -> bool {
+        self.racks() == other.racks()
+            && self.bag == other.bag
+            && self.max_duplicate_letters == other.max_duplicate_letters
+    }
+}
+
+impl Eq for Hands {}
+
+// Only the tiles in players' hands identify a position, so equal hands always hash the same
+impl Hash for Hands {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.racks().hash(state);
     }
 }
 
@@ -52,8 +240,84 @@ impl Default for Hands {
 
 #[cfg(test)]
 mod tests {
-    use super::super::bag::tests as TileUtils;
+    use super::super::bag::{tests as TileUtils, BLANK};
     use super::*;
+    use crate::board::{tests as BoardUtils, Coordinate, Direction};
+
+    #[test]
+    fn seeded_hands_draw_the_same_tiles() {
+        let mut first = Hands::new_seeded(2, 7, TileBag::default(), 7);
+        let mut second = Hands::new_seeded(2, 7, TileBag::default(), 7);
+        assert_eq!(first, second);
+        assert_ne!(first, Hands::new_seeded(2, 7, TileBag::default(), 8));
+
+        // Replacement tiles are drawn identically for the rest of the game
+        for turn in 0..50 {
+            let player = turn % 2;
+            let tile = first.get_hand(player)[turn % 7];
+            first.use_tile(player, tile).unwrap();
+            second.use_tile(player, tile).unwrap();
+            assert_eq!(first, second);
+        }
+    }
+
+    #[test]
+    fn hashing_ignores_tile_order() {
+        let hash = |hands: &Hands| {
+            let mut hasher = std::collections::hash_map::DefaultHasher::new();
+            hands.hash(&mut hasher);
+            hasher.finish()
+        };
+        let mut h = Hands::new(2, 3, TileUtils::trivial_bag());
+        h.hands = vec![vec!['A', 'B', 'C'], vec!['D', 'E', 'E']];
+        let mut reordered = h.clone();
+        reordered.hands = vec![vec!['C', 'A', 'B'], vec!['E', 'D', 'E']];
+        assert_eq!(h, reordered);
+        assert_eq!(hash(&h), hash(&reordered));
+
+        let mut different = h.clone();
+        different.hands = vec![vec!['A', 'B', 'C'], vec!['D', 'D', 'E']];
+        assert_ne!(h, different);
+        assert_ne!(hash(&h), hash(&different));
+
+        // The same tiles in different players' hands is a different position
+        let mut exchanged = h.clone();
+        exchanged.hands = vec![vec!['D', 'E', 'E'], vec!['A', 'B', 'C']];
+        assert_ne!(h, exchanged);
+        assert_ne!(hash(&h), hash(&exchanged));
+    }
+
+    #[test]
+    fn rank_tiles() {
+        let b = BoardUtils::from_string(
+            [
+                "_ _ B _ _",
+                "_ _ I _ _",
+                "_ _ _ _ _",
+                "_ _ _ _ _",
+                "_ _ _ _ _",
+            ]
+            .join("\n"),
+            vec![Coordinate { x: 2, y: 0 }, Coordinate { x: 2, y: 4 }],
+            vec![Direction::South, Direction::North],
+        )
+        .unwrap();
+        let mut h = Hands::new(2, 4, TileUtils::trivial_bag());
+        h.hands[0] = vec!['X', 'G', 'T', 'X'];
+
+        // Only G can finish a word, and duplicate tiles are only ranked once
+        let ranked = h.rank_tiles(0, &b, &Judge::new(vec!["BIG"]));
+        assert_eq!(ranked, vec![('G', 3.0), ('X', 0.0), ('T', 0.0)]);
+
+        // Longer words rank higher
+        let ranked = h.rank_tiles(0, &b, &Judge::new(vec!["BIG", "BIT", "XI"]));
+        assert_eq!(ranked, vec![('G', 3.0), ('T', 3.0), ('X', 2.0)]);
+
+        // A blank ranks by the best letter it could stand for
+        h.hands[0] = vec!['X', BLANK];
+        let ranked = h.rank_tiles(0, &b, &Judge::new(vec!["BIG", "XI"]));
+        assert_eq!(ranked, vec![(BLANK, 3.0), ('X', 2.0)]);
+    }
 
     #[test]
     fn default() {
@@ -97,6 +361,29 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn duplicate_limit() {
+        let mut dist = [0; 26];
+        dist[0] = 4;
+        dist[4] = 4;
+        // This seed deals three Es to the first player when there's no limit
+        let seeded = || TileBag::new(dist).with_seed(6);
+        assert_eq!(Hands::new(2, 3, seeded()).hands[0], vec!['E', 'E', 'E']);
+
+        // With a limit of two, the third E is redrawn
+        let mut h = Hands::with_duplicate_limit(2, 3, seeded(), 2);
+        assert_eq!(h.hands[0], vec!['E', 'E', 'A']);
+
+        // The limit is kept as hands are replenished
+        for _ in 0..20 {
+            for player in 0..2 {
+                h.use_tile(player, h.hands[player][0]).unwrap();
+                assert!(h.hands[player].iter().filter(|&&t| t == 'E').count() <= 2);
+                assert_eq!(h.hands[player].len(), 3);
+            }
+        }
+    }
+
     #[test]
     fn get_errors() {
         let mut h = Hands::new(2, 7, TileUtils::trivial_bag());
@@ -112,4 +399,31 @@ mod tests {
             })
         );
     }
+
+    #[test]
+    fn take_back() {
+        let mut h = Hands::new(2, 3, TileUtils::a_b_bag());
+        let tile = h.hands[0][1];
+        h.use_tile(0, tile).unwrap();
+        let replacement = h.hands[0][1];
+        assert_eq!(h.take_back(0, tile), Ok(Some(replacement)));
+        assert_eq!(h.hands[0][1], tile);
+
+        // Nothing has been drawn since, so there's no room for another tile
+        assert_eq!(
+            h.take_back(0, tile),
+            Err(GamePlayError::HandFull { player: 0 })
+        );
+        assert_eq!(h.hands[0].len(), 3);
+
+        // Extra tiles make room for themselves
+        h.deal_extra(0, 1).unwrap();
+        h.hands[0].pop();
+        assert_eq!(h.take_back(0, tile), Ok(None));
+        assert_eq!(h.hands[0].len(), 4);
+        assert_eq!(
+            h.take_back(2, tile),
+            Err(GamePlayError::NonExistentPlayer { index: 2 })
+        );
+    }
 }
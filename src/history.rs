@@ -0,0 +1,416 @@
+use std::collections::HashSet;
+
+use super::board::{Board, Coordinate};
+use super::hand::Hands;
+use super::judge::{Judge, Outcome};
+use super::moves::{Move, MoveReport};
+use crate::error::GamePlayError;
+
+// A record of a game from the position before its first move, for replaying and checking games after the fact
+#[derive(Clone, Debug, Default)]
+pub struct GameLog {
+    pub initial_board: Board,
+    pub initial_hands: Hands,
+    pub turns: Vec<Turn>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Turn {
+    pub played: Move,
+    pub position_hash: u64, // The board's canonical hash after the move was played
+}
+
+// Everything that happens in a game, in order, for narrating it to people watching rather than playing
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SpectatorEvent {
+    Moved { played: Move },
+    Battle { outcome: Outcome },
+    Captured { squares: Vec<Coordinate> },
+    AttackRepelled { lost: Vec<(Coordinate, char)> },
+    Resigned { player: usize },
+    Won { player: usize },
+    Drawn,
+}
+
+impl SpectatorEvent {
+    // The events describing a move and its consequences
+    pub fn from_move(played: Move, report: MoveReport) -> Vec<Self> {
+        let mut events = vec![SpectatorEvent::Moved { played }];
+        match report.outcome {
+            Outcome::NoBattle => {}
+            Outcome::AttackerWins(_) => events.extend([
+                SpectatorEvent::Battle {
+                    outcome: report.outcome,
+                },
+                SpectatorEvent::Captured {
+                    squares: report.defeated_squares,
+                },
+            ]),
+            Outcome::DefenderWins => events.extend([
+                SpectatorEvent::Battle {
+                    outcome: report.outcome,
+                },
+                SpectatorEvent::AttackRepelled {
+                    lost: report.lost_attackers,
+                },
+            ]),
+            Outcome::Mutual(_) => events.extend([
+                SpectatorEvent::Battle {
+                    outcome: report.outcome,
+                },
+                SpectatorEvent::Captured {
+                    squares: report.defeated_squares,
+                },
+                SpectatorEvent::AttackRepelled {
+                    lost: report.lost_attackers,
+                },
+            ]),
+        }
+        events
+    }
+}
+
+impl GameLog {
+    pub fn new(initial_board: Board, initial_hands: Hands) -> Self {
+        Self {
+            initial_board,
+            initial_hands,
+            turns: vec![],
+        }
+    }
+
+    // Records a move that has just been played, along with the board it resulted in
+    pub fn record(&mut self, played: Move, board: &Board) {
+        self.turns.push(Turn {
+            played,
+            position_hash: board.canonical_hash(),
+        });
+    }
+
+    // Replays the game from the start, reporting what each move did.
+    // If a move can't be replayed, e.g. because a different judge changed an earlier battle, returns its index along with why it failed.
+    pub fn timeline(&self, judge: &Judge) -> Result<Vec<MoveReport>, (usize, GamePlayError)> {
+        let mut board = self.initial_board.clone();
+        let mut hands = self.initial_hands.clone();
+        self.turns
+            .iter()
+            .enumerate()
+            .map(|(index, turn)| {
+                board
+                    .make_move_reporting(turn.played.clone(), &mut hands, judge)
+                    .map_err(|err| (index, err))
+            })
+            .collect()
+    }
+
+    // Takes back the last move, bringing the board and hands back to how they were before it, and returns the move.
+    // Swaps are simply swapped back, but placements are undone by replaying the game without them, since battles can't be reversed.
+    pub fn undo(
+        &mut self,
+        board: &mut Board,
+        hands: &mut Hands,
+        judge: &Judge,
+    ) -> Result<Option<Move>, GamePlayError> {
+        let last = match self.turns.last() {
+            Some(turn) => turn.played.clone(),
+            None => return Ok(None),
+        };
+        match last {
+            Move::Swap { positions, .. } => board.undo_swap(positions)?,
+            Move::Place { .. } => {
+                let mut replayed_board = self.initial_board.clone();
+                let mut replayed_hands = self.initial_hands.clone();
+                for turn in &self.turns[..self.turns.len() - 1] {
+                    replayed_board.make_move(turn.played.clone(), &mut replayed_hands, judge)?;
+                }
+                *board = replayed_board;
+                *hands = replayed_hands;
+            }
+        }
+        self.turns.pop();
+        Ok(Some(last))
+    }
+
+    // One row per move, replaying the game to find out what each move did. The tile columns count each player's tiles after the move.
+    // If a move can't be replayed, e.g. because a different judge changed an earlier battle, the rows stop before it.
+    pub fn to_csv(&self, judge: &Judge) -> String {
+        let players = self.initial_board.get_orientations().len();
+        let mut header = vec![
+            String::from("turn"),
+            String::from("player"),
+            String::from("move"),
+            String::from("position"),
+            String::from("words"),
+            String::from("outcome"),
+        ];
+        header.extend((0..players).map(|player| format!("tiles_{}", player)));
+        let mut rows = vec![header.join(",")];
+
+        let mut board = self.initial_board.clone();
+        let mut hands = self.initial_hands.clone();
+        for (turn, Turn { played, .. }) in self.turns.iter().enumerate() {
+            let report = match board.make_move_reporting(played.clone(), &mut hands, judge) {
+                Ok(report) => report,
+                Err(_) => break,
+            };
+            let (player, kind, position) = match played {
+                Move::Place {
+                    player, position, ..
+                } => (*player, "place", position.to_string()),
+                Move::Swap { player, positions } => (
+                    *player,
+                    "swap",
+                    format!("{} {}", positions[0], positions[1]),
+                ),
+            };
+            let words: Vec<&str> = report
+                .formed_words
+                .iter()
+                .map(|formed| formed.word.as_str())
+                .collect();
+            let outcome = match report.outcome {
+                Outcome::NoBattle => "no battle",
+                Outcome::AttackerWins(_) => "attacker won",
+                Outcome::DefenderWins => "defender won",
+                Outcome::Mutual(_) => "both lost",
+            };
+            let mut row = vec![
+                (turn + 1).to_string(),
+                player.to_string(),
+                kind.to_string(),
+                csv_field(&position),
+                csv_field(&words.join(" ")),
+                outcome.to_string(),
+            ];
+            row.extend((0..players).map(|player| board.tiles_of(player).len().to_string()));
+            rows.push(row.join(","));
+        }
+        rows.join("\n")
+    }
+
+    // Whether an unbroken run of swaps ever returns the board to a position seen earlier in that run.
+    // Swaps can't change which squares are occupied, so such a run can go on forever without the game progressing.
+    pub fn has_non_progressing_cycle(&self) -> bool {
+        let mut previous = self.initial_board.canonical_hash();
+        let mut seen = HashSet::new();
+        for turn in &self.turns {
+            match turn.played {
+                Move::Place { .. } => seen.clear(),
+                Move::Swap { .. } => {
+                    seen.insert(previous);
+                    if seen.contains(&turn.position_hash) {
+                        return true;
+                    }
+                }
+            }
+            previous = turn.position_hash;
+        }
+        false
+    }
+}
+
+// Quotes a CSV field if it contains anything that would otherwise split it
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::bag::tests as TileUtils;
+    use super::*;
+    use crate::board::{tests as BoardUtils, Coordinate, Direction, Square};
+    use crate::moves::tests::short_dict;
+
+    fn log_of(board: Board, moves: Vec<Move>) -> GameLog {
+        let mut hands = Hands::new(2, 7, TileUtils::trivial_bag());
+        let judge = short_dict();
+        let mut log = GameLog::new(board.clone(), hands.clone());
+        let mut board = board;
+        for played in moves {
+            board.make_move(played.clone(), &mut hands, &judge).unwrap();
+            log.record(played, &board);
+        }
+        log
+    }
+
+    #[test]
+    fn timeline() {
+        let board = BoardUtils::from_string(
+            [
+                "_ S X _ _",
+                "_ T _ _ _",
+                "_ R _ _ _",
+                "_ _ _ _ _",
+                "_ _ T _ _",
+            ]
+            .join("\n"),
+            vec![Coordinate { x: 2, y: 0 }, Coordinate { x: 2, y: 4 }],
+            vec![Direction::North, Direction::South],
+        )
+        .unwrap();
+        let place = |player, x, y| Move::Place {
+            player,
+            tile: 'A',
+            position: Coordinate { x, y },
+        };
+        let log = log_of(board, vec![place(0, 3, 0), place(1, 2, 3), place(0, 1, 3)]);
+
+        let timeline = log.timeline(&short_dict()).unwrap();
+        assert_eq!(timeline.len(), log.turns.len());
+        assert_eq!(timeline[0].outcome, Outcome::NoBattle);
+        assert_eq!(timeline[1].outcome, Outcome::NoBattle);
+        assert_eq!(timeline[2].outcome, Outcome::AttackerWins(vec![0]));
+        assert_eq!(
+            timeline[2].defeated_squares,
+            vec![Coordinate { x: 2, y: 3 }, Coordinate { x: 2, y: 4 }]
+        );
+
+        // A move that no longer fits the game is reported along with where it was played
+        let mut log = log;
+        let board = log.initial_board.clone();
+        log.record(place(0, 1, 0), &board);
+        assert_eq!(
+            log.timeline(&short_dict()),
+            Err((3, GamePlayError::OccupiedPlace))
+        );
+    }
+
+    #[test]
+    fn to_csv() {
+        let board = BoardUtils::from_string(
+            [
+                "_ S X _ _",
+                "_ T _ _ _",
+                "_ R _ _ _",
+                "_ _ _ _ _",
+                "_ _ T _ _",
+            ]
+            .join("\n"),
+            vec![Coordinate { x: 2, y: 0 }, Coordinate { x: 2, y: 4 }],
+            vec![Direction::North, Direction::South],
+        )
+        .unwrap();
+        let place = |player, x, y| Move::Place {
+            player,
+            tile: 'A',
+            position: Coordinate { x, y },
+        };
+        let swap = Move::Swap {
+            player: 0,
+            positions: [Coordinate { x: 1, y: 0 }, Coordinate { x: 1, y: 1 }],
+        };
+        let log = log_of(
+            board,
+            vec![place(0, 3, 0), place(1, 2, 3), place(0, 1, 3), swap],
+        );
+
+        let csv = log.to_csv(&short_dict());
+        let rows: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            rows,
+            vec![
+                "turn,player,move,position,words,outcome,tiles_0,tiles_1",
+                "1,0,place,\"(3, 0)\",AXS,no battle,5,1",
+                "2,1,place,\"(2, 3)\",AT,no battle,5,2",
+                "3,0,place,\"(1, 3)\",ARTS,attacker won,6,0",
+                "4,0,swap,\"(1, 0) (1, 1)\",,no battle,6,0",
+            ]
+        );
+    }
+
+    #[test]
+    fn undo() {
+        let initial_board = BoardUtils::from_string(
+            [
+                "_ _ B _ _",
+                "_ _ I _ _",
+                "_ _ G _ _",
+                "_ _ _ _ _",
+                "_ _ _ _ _",
+            ]
+            .join("\n"),
+            vec![Coordinate { x: 2, y: 0 }, Coordinate { x: 2, y: 4 }],
+            vec![Direction::North, Direction::South],
+        )
+        .unwrap();
+        let initial_hands = Hands::new(2, 7, TileUtils::trivial_bag());
+        let judge = short_dict();
+        let swap = Move::Swap {
+            player: 0,
+            positions: [Coordinate { x: 2, y: 1 }, Coordinate { x: 2, y: 2 }],
+        };
+        let place = Move::Place {
+            player: 1,
+            tile: 'A',
+            position: Coordinate { x: 2, y: 4 },
+        };
+
+        let mut log = GameLog::new(initial_board.clone(), initial_hands.clone());
+        let mut board = initial_board.clone();
+        let mut hands = initial_hands.clone();
+        for played in [place.clone(), swap.clone()] {
+            board.make_move(played.clone(), &mut hands, &judge).unwrap();
+            log.record(played, &board);
+        }
+        assert_eq!(
+            board.get(Coordinate { x: 2, y: 1 }),
+            Ok(Square::Occupied(0, 'G', false))
+        );
+
+        // The swap is undone first, leaving the placement before it in place
+        assert_eq!(log.undo(&mut board, &mut hands, &judge), Ok(Some(swap)));
+        assert_eq!(
+            board.get(Coordinate { x: 2, y: 1 }),
+            Ok(Square::Occupied(0, 'I', false))
+        );
+        assert_eq!(
+            board.get(Coordinate { x: 2, y: 4 }),
+            Ok(Square::Occupied(1, 'A', false))
+        );
+
+        assert_eq!(log.undo(&mut board, &mut hands, &judge), Ok(Some(place)));
+        assert_eq!(board, initial_board);
+        assert_eq!(hands, initial_hands);
+        assert_eq!(log.undo(&mut board, &mut hands, &judge), Ok(None));
+    }
+
+    #[test]
+    fn non_progressing_cycles() {
+        let board = BoardUtils::from_string(
+            [
+                "_ _ B _ _",
+                "_ _ I _ _",
+                "_ _ G _ _",
+                "_ _ _ _ _",
+                "_ _ _ _ _",
+            ]
+            .join("\n"),
+            vec![Coordinate { x: 2, y: 0 }, Coordinate { x: 2, y: 4 }],
+            vec![Direction::North, Direction::South],
+        )
+        .unwrap();
+        let swap = |player| Move::Swap {
+            player,
+            positions: [Coordinate { x: 2, y: 1 }, Coordinate { x: 2, y: 2 }],
+        };
+        let place = |player, x, y| Move::Place {
+            player,
+            tile: 'A',
+            position: Coordinate { x, y },
+        };
+
+        let progressing = log_of(board.clone(), vec![place(0, 1, 1), place(1, 2, 4), swap(0)]);
+        assert!(!progressing.has_non_progressing_cycle());
+
+        let swap_back = log_of(board.clone(), vec![swap(0), swap(0)]);
+        assert!(swap_back.has_non_progressing_cycle());
+
+        // Placing a tile between the swaps breaks the cycle
+        let interrupted = log_of(board, vec![swap(0), place(1, 2, 4), swap(0)]);
+        assert!(!interrupted.has_non_progressing_cycle());
+    }
+}
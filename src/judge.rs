@@ -1,108 +1,468 @@
-use super::board::{Board, Square};
+use super::board::{Board, Coordinate, Square};
+use super::dictionary::Dictionary;
 use std::collections::HashSet;
 use std::fs::File;
-use std::io::{prelude::*, BufReader};
+use std::io::{self, prelude::*, BufReader};
+use std::path::Path;
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
 
-#[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub enum Outcome {
     AttackerWins(Vec<usize>), // A list of specific defenders who are defeated
     DefenderWins,             // If the defender wins, all attackers lose
+    Mutual(Vec<usize>), // Under the mutual tie policy, the listed defenders are defeated and all the attackers lose too
+    #[default]
     NoBattle,
 }
 
+// How a battle was decided, in the order that `battle` checks things
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BattleTrace {
+    pub attackers_valid: Vec<bool>,
+    pub longest_attacker: usize,
+    pub defenders_valid: Vec<bool>,
+    pub defenders_too_short: Vec<bool>, // Whether each defender is too short to hold off the longest attacker, whether or not it's valid
+    pub outcome: Outcome,
+}
+
+// The outcome of a battle along with the words themselves, for callers that don't have the defenders to look indices up in
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BattleReport {
+    pub outcome: Outcome,
+    pub defeated_words: Vec<String>, // Empty unless the attacker wins
+    pub winning_attacker: Option<String>, // The longest attacking word, which decides which defenders are too short
+}
+
+// Who wins when a valid defending word is exactly long enough to hold off the longest attacker, i.e. exactly `defense_bonus` letters shorter
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TiePolicy {
+    #[default]
+    DefenderWins,
+    AttackerWins,
+    Mutual, // The defender is defeated, but so is the attacker
+}
+
+// How a player wins the game
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WinCondition {
+    #[default]
+    ReachOppositeEdge,
+    OccupyQuadrants {
+        needed: usize,
+    }, // Hold the most tiles in at least this many of the board's four quarters
+}
+
 pub struct Judge {
-    dictionary: HashSet<String>,
+    dictionary: Box<dyn Dictionary>,
+    strip_diacritics: bool, // Whether accents are removed from words before they're judged, e.g. so that "CAFÉ" matches "cafe"
+    defense_bonus: usize, // How many letters longer than a defending word an attacking word has to be before it's strong enough to beat it, less one
+    min_word_length: usize, // Shorter words are never valid, even if they're in the dictionary
+    tie_policy: TiePolicy,
+    allowed: HashSet<String>, // House rules layered over the dictionary, so that words can be toggled whatever the backend
+    banned: HashSet<String>,
 }
 
+// By default an attacking word has to be at least 2 letters longer than a valid defending word to beat it
+const DEFAULT_DEFENSE_BONUS: usize = 1;
+
+// By default every word in the dictionary counts, as it always has. Standard play opts in to a minimum of 2, so that a single tile doesn't spell a word.
+const DEFAULT_MIN_WORD_LENGTH: usize = 1;
+
 impl Default for Judge {
     fn default() -> Self {
-        let file = File::open("./dictionary.txt").expect("file missing"); // collins2018 list
-        let reader = BufReader::new(file);
-        let mut dictionary = HashSet::new();
-
-        for line in reader.lines() {
-            dictionary.insert(line.expect("bad encoding").to_lowercase());
-        }
-        Self { dictionary }
+        Self::from_file("./dictionary.txt").expect("file missing") // collins2018 list
     }
 }
 
 impl Judge {
     pub fn new(words: Vec<&str>) -> Self {
-        let mut dictionary = HashSet::new();
-        for word in words {
-            dictionary.insert(word.to_lowercase());
+        Self::with_backend::<HashSet<String>>(words)
+    }
+
+    // A judge that keeps its words in the given kind of dictionary, e.g. `FstDictionary` to save memory or answer prefix queries quickly
+    pub fn with_backend<D: Dictionary + FromIterator<String> + 'static>(words: Vec<&str>) -> Self {
+        Self::build::<D, _>(words.into_iter().map(String::from), false)
+    }
+
+    fn build<D: Dictionary + FromIterator<String> + 'static, I: IntoIterator<Item = String>>(
+        words: I,
+        strip_diacritics: bool,
+    ) -> Self {
+        let dictionary: D = words
+            .into_iter()
+            .map(|word| normalize(&word, strip_diacritics))
+            .collect();
+        Self {
+            dictionary: Box::new(dictionary),
+            strip_diacritics,
+            defense_bonus: DEFAULT_DEFENSE_BONUS,
+            min_word_length: DEFAULT_MIN_WORD_LENGTH,
+            tie_policy: TiePolicy::default(),
+            allowed: HashSet::new(),
+            banned: HashSet::new(),
         }
-        Self { dictionary }
     }
 
-    // A player wins if they reach the opposite side of the board
-    // TODO: accept a config that chooses between different win conditions, like occupying enough quadrants
-    // TODO: error (or possibly return a tie) if there are multiple winners - this assume turn based play
-    // TODO: put this somewhere better, it conceptually works as a judge associated function, but it only uses values from the board
-    pub fn winner(board: &Board) -> Option<usize> {
-        for (potential_winner, orientation) in board.get_orientations().iter().enumerate() {
-            for coordinate in board.get_near_edge(orientation.opposite()) {
-                if let Ok(Square::Occupied(occupier, _)) = board.get(coordinate) {
-                    if potential_winner == occupier {
-                        return Some(potential_winner);
+    // A judge for variants where defenders have a bigger or smaller advantage, e.g. with a bonus of 0 an attacking word only has to be longer than the defender
+    pub fn with_defense_bonus(words: Vec<&str>, defense_bonus: usize) -> Self {
+        Self {
+            defense_bonus,
+            ..Self::new(words)
+        }
+    }
+
+    // A judge for variants that rule out short words, e.g. with a minimum of 3 two letter words like "AN" and "OF" don't count
+    pub fn with_min_length(words: Vec<&str>, min_word_length: usize) -> Self {
+        Self {
+            min_word_length,
+            ..Self::new(words)
+        }
+    }
+
+    pub fn with_tie_policy(mut self, tie_policy: TiePolicy) -> Self {
+        self.tie_policy = tie_policy;
+        self
+    }
+
+    // Loads a newline separated word list from anywhere, e.g. from bytes embedded in the binary with a `Cursor`
+    pub fn from_reader<R: BufRead>(reader: R) -> io::Result<Self> {
+        Self::from_reader_normalized(reader, false)
+    }
+
+    pub fn from_file<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::from_reader(BufReader::new(File::open(path)?))
+    }
+
+    // Loads a newline separated word list, optionally removing accents from it and from every word that is later judged
+    pub fn from_reader_normalized<R: Read>(reader: R, strip_diacritics: bool) -> io::Result<Self> {
+        Self::from_reader_with_backend::<HashSet<String>, R>(reader, strip_diacritics)
+    }
+
+    pub fn from_reader_with_backend<D: Dictionary + FromIterator<String> + 'static, R: Read>(
+        reader: R,
+        strip_diacritics: bool,
+    ) -> io::Result<Self> {
+        let words = BufReader::new(reader)
+            .lines()
+            .collect::<io::Result<Vec<String>>>()?;
+        Ok(Self::build::<D, _>(words, strip_diacritics))
+    }
+
+    // The only player who has won under the given condition, if any
+    pub fn check_winner(board: &Board, condition: WinCondition) -> Option<usize> {
+        match condition {
+            WinCondition::ReachOppositeEdge => Self::winner(board),
+            WinCondition::OccupyQuadrants { needed } => {
+                let owners = Self::quadrant_owners(board);
+                let winners: Vec<usize> = (0..board.get_orientations().len())
+                    .filter(|&player| {
+                        owners
+                            .iter()
+                            .filter(|&&owner| owner == Some(player))
+                            .count()
+                            >= needed
+                    })
+                    .collect();
+                match winners[..] {
+                    [winner] => Some(winner),
+                    _ => None,
+                }
+            }
+        }
+    }
+
+    // Who has the most tiles in each quarter of the board, from the top left going clockwise, or None where nobody has a clear majority.
+    // On boards with an odd width or height the middle line belongs to the bottom or right quarters.
+    fn quadrant_owners(board: &Board) -> [Option<usize>; 4] {
+        let (mid_x, mid_y) = (board.width() / 2, board.height() / 2);
+        let quadrant = |x: usize, y: usize| match (x < mid_x, y < mid_y) {
+            (true, true) => 0,
+            (false, true) => 1,
+            (false, false) => 2,
+            (true, false) => 3,
+        };
+        let players = board.get_orientations().len();
+        let mut counts = vec![vec![0; players]; 4];
+        for y in 0..board.height() {
+            for x in 0..board.width() {
+                if let Ok(Square::Occupied(player, _, _)) = board.get(Coordinate { x, y }) {
+                    if let Some(count) = counts[quadrant(x, y)].get_mut(player) {
+                        *count += 1;
                     }
                 }
             }
         }
-        None
+        let mut owners = [None; 4];
+        for (owner, counts) in owners.iter_mut().zip(counts) {
+            let most = counts.iter().copied().max().unwrap_or(0);
+            if most > 0 && counts.iter().filter(|&&count| count == most).count() == 1 {
+                *owner = counts.iter().position(|&count| count == most);
+            }
+        }
+        owners
+    }
+
+    // A player wins if they reach the opposite side of the board
+    // TODO: put this somewhere better, it conceptually works as a judge associated function, but it only uses values from the board
+    pub fn winners(board: &Board) -> Vec<usize> {
+        (0..board.get_orientations().len())
+            .filter(|&potential_winner| {
+                board.goal_edges(potential_winner).into_iter().any(|side| {
+                    board.get_near_edge(side).into_iter().any(|coordinate| {
+                        matches!(board.get(coordinate), Ok(Square::Occupied(occupier, _, _)) if occupier == potential_winner)
+                    })
+                })
+            })
+            .collect()
+    }
+
+    // The only player who has won, or None if nobody has or if several players reached their goals at once, which is a draw
+    pub fn winner(board: &Board) -> Option<usize> {
+        match Self::winners(board)[..] {
+            [winner] => Some(winner),
+            _ => None,
+        }
     }
 
     // If there are no attackers or no defenders there is no battle
     // The defender wins if any attacking word is invalid, or all defending words are valid and stronger than the longest attacking words
     // Otherwise the attacker wins
     //
-    // There is a defender's advantage, so an attacking word has to be at least 2 letters longer than a defending word to be stronger than it.
+    // There is a defender's advantage, so by default an attacking word has to be at least 2 letters longer than a defending word to be stronger than it.
     pub fn battle(&self, attackers: Vec<String>, defenders: Vec<String>) -> Outcome {
+        Self::battle_with_bonus(
+            &self.spec(&attackers),
+            &self.spec(&defenders),
+            self.defense_bonus,
+            self.tie_policy,
+        )
+    }
+
+    // Every step of the battle's decision as data, e.g. for analysing battles or explaining them in a UI
+    pub fn battle_trace(&self, attackers: &[String], defenders: &[String]) -> BattleTrace {
+        let attackers = self.spec(attackers);
+        let defenders = self.spec(defenders);
+        let longest_attacker = attackers
+            .iter()
+            .map(|&(length, _)| length)
+            .max()
+            .unwrap_or(0);
+        BattleTrace {
+            attackers_valid: attackers.iter().map(|&(_, valid)| valid).collect(),
+            longest_attacker,
+            defenders_valid: defenders.iter().map(|&(_, valid)| valid).collect(),
+            defenders_too_short: defenders
+                .iter()
+                .map(|&(length, _)| length + self.defense_bonus < longest_attacker)
+                .collect(),
+            outcome: Self::battle_with_bonus(
+                &attackers,
+                &defenders,
+                self.defense_bonus,
+                self.tie_policy,
+            ),
+        }
+    }
+
+    // The same as `battle`, but naming the defeated words and the attacking word that beat them
+    pub fn battle_detailed(&self, attackers: Vec<String>, defenders: Vec<String>) -> BattleReport {
+        let outcome = self.battle(attackers.clone(), defenders.clone());
+        match &outcome {
+            Outcome::AttackerWins(losers) => BattleReport {
+                defeated_words: losers.iter().map(|&i| defenders[i].clone()).collect(),
+                // The longest attacker, measured the same way as in the battle, with ties going to the first one given
+                winning_attacker: attackers
+                    .iter()
+                    .enumerate()
+                    .max_by_key(|&(index, word)| (self.length(word), std::cmp::Reverse(index)))
+                    .map(|(_, word)| word.clone()),
+                outcome,
+            },
+            Outcome::Mutual(losers) => BattleReport {
+                defeated_words: losers.iter().map(|&i| defenders[i].clone()).collect(),
+                winning_attacker: None,
+                outcome,
+            },
+            _ => BattleReport {
+                outcome,
+                defeated_words: vec![],
+                winning_attacker: None,
+            },
+        }
+    }
+
+    // The length and validity of each word, which is all that a battle depends on
+    fn spec(&self, words: &[String]) -> Vec<(usize, bool)> {
+        words
+            .iter()
+            .map(|word| self.length(word))
+            .zip(self.validate_all(words))
+            .collect()
+    }
+
+    // Decides a battle from just the length and validity of each word, so that clients can reason about hypothetical battles without the words themselves.
+    // Follows exactly the same rules as `battle` with the default defender's advantage, and the defeated defenders are indices into `defenders`.
+    pub fn battle_by_spec(attackers: &[(usize, bool)], defenders: &[(usize, bool)]) -> Outcome {
+        Self::battle_with_bonus(
+            attackers,
+            defenders,
+            DEFAULT_DEFENSE_BONUS,
+            TiePolicy::default(),
+        )
+    }
+
+    fn battle_with_bonus(
+        attackers: &[(usize, bool)],
+        defenders: &[(usize, bool)],
+        defense_bonus: usize,
+        tie_policy: TiePolicy,
+    ) -> Outcome {
         // If there are no attackers or no defenders there is no battle
         if attackers.is_empty() || defenders.is_empty() {
             return Outcome::NoBattle;
         }
 
         // The defender wins if any attacking word is invalid
-        if attackers.iter().any(|word| !self.valid(word)) {
+        if attackers.iter().any(|&(_, valid)| !valid) {
             return Outcome::DefenderWins;
         }
 
         // The defender wins if all their words are valid and long enough to defend against the longest attacker
         let longest_attacker = attackers
             .iter()
-            .reduce(|longest, curr| {
-                if curr.len() > longest.len() {
-                    curr
-                } else {
-                    longest
-                }
-            })
+            .map(|&(length, _)| length)
+            .max()
             .expect("already checked length");
 
+        // Valid defenders that are exactly long enough only fall if the tie policy says so
+        let tied = |length: usize| length + defense_bonus == longest_attacker;
         let weak_defenders: Vec<usize> = defenders // Indices of the weak defenders
             .iter()
             .enumerate()
-            .filter(|(_, word)| !self.valid(word) || word.len() + 1 < longest_attacker.len())
+            .filter(|(_, &(length, valid))| {
+                !valid
+                    || length + defense_bonus < longest_attacker
+                    || (tie_policy != TiePolicy::DefenderWins && tied(length))
+            })
             .map(|(index, _)| index)
             .collect();
         if weak_defenders.is_empty() {
             return Outcome::DefenderWins;
         }
 
-        // Otherwise the attacker wins
+        // Otherwise the attacker wins, unless a tie takes the attackers down with the defenders
+        let any_tied = defenders
+            .iter()
+            .any(|&(length, valid)| valid && tied(length));
+        if tie_policy == TiePolicy::Mutual && any_tied {
+            return Outcome::Mutual(weak_defenders);
+        }
         Outcome::AttackerWins(weak_defenders)
     }
 
-    fn valid<S: AsRef<str>>(&self, word: S) -> bool {
-        self.dictionary.contains(&word.as_ref().to_lowercase())
+    // The same as `battle` with exactly one attacker and one defender, but without allocating any vectors until the attacker wins
+    pub fn battle_single(&self, attacker: &str, defender: &str) -> Outcome {
+        if self.strip_diacritics || self.tie_policy != TiePolicy::DefenderWins {
+            return self.battle(vec![attacker.to_string()], vec![defender.to_string()]);
+        }
+        if !self.valid(attacker) || !self.weak(defender, self.length(attacker)) {
+            Outcome::DefenderWins
+        } else {
+            Outcome::AttackerWins(vec![0])
+        }
+    }
+
+    // A defending word is weak if it is invalid, or not long enough to hold off an attacking word of the given length
+    pub fn weak(&self, defender: &str, attacker_length: usize) -> bool {
+        !self.valid(defender)
+            || self.length(defender) + self.defense_bonus < attacker_length
+            || (self.tie_policy != TiePolicy::DefenderWins
+                && self.length(defender) + self.defense_bonus == attacker_length)
+    }
+
+    // How many letters a word has in battle, counting characters rather than bytes, after any accents are stripped
+    fn length(&self, word: &str) -> usize {
+        self.normalize(word).chars().count()
+    }
+
+    // Checks a batch of words in one go, in the same order as the words
+    pub fn validate_all(&self, words: &[String]) -> Vec<bool> {
+        words.iter().map(|word| self.valid(word)).collect()
+    }
+
+    pub fn valid<S: AsRef<str>>(&self, word: S) -> bool {
+        let word = self.normalize(word.as_ref());
+        word.chars().count() >= self.min_word_length && self.contains_normalized(&word)
+    }
+
+    // Whether the word is in the dictionary, including any house rules, regardless of the minimum word length
+    pub fn contains(&self, word: &str) -> bool {
+        self.contains_normalized(&self.normalize(word))
+    }
+
+    fn contains_normalized(&self, word: &str) -> bool {
+        !self.banned.contains(word)
+            && (self.allowed.contains(word) || self.dictionary.contains(word))
+    }
+
+    // Allows a word for the rest of the session, e.g. when players agree on a house rule
+    pub fn add_word(&mut self, word: &str) {
+        let word = self.normalize(word);
+        self.banned.remove(&word);
+        self.allowed.insert(word);
+    }
+
+    // Bans a word for the rest of the session, returning whether it was allowed before.
+    // Only words from the dictionary itself need banning, so words that were never there don't pile up in the ban list.
+    pub fn remove_word(&mut self, word: &str) -> bool {
+        let word = self.normalize(word);
+        let present = self.contains_normalized(&word);
+        if present {
+            self.allowed.remove(&word);
+            if self.dictionary.contains(&word) {
+                self.banned.insert(word);
+            }
+        }
+        present
+    }
+
+    // Whether any word in the dictionary starts with the prefix, e.g. to stop extending a line of tiles that can't become a word.
+    // Banned words still count, so this can be true for a prefix that only leads to banned words.
+    // Words allowed by house rules are scanned one by one, so this slows down as more words are added.
+    pub fn has_prefix<S: AsRef<str>>(&self, prefix: S) -> bool {
+        let prefix = self.normalize(prefix.as_ref());
+        self.dictionary.has_prefix(&prefix)
+            || self.allowed.iter().any(|word| word.starts_with(&prefix))
+    }
+
+    fn normalize(&self, word: &str) -> String {
+        normalize(word, self.strip_diacritics)
+    }
+}
+
+// Words are stored in uppercase, which is how the board spells them.
+// Stripping diacritics decomposes each letter and drops the combining marks, so é becomes e. Letters that
+// Unicode doesn't treat as a base letter with a mark, such as ø and ł, are left as they are.
+fn normalize(word: &str, strip_diacritics: bool) -> String {
+    if strip_diacritics {
+        word.nfd()
+            .filter(|&c| !is_combining_mark(c))
+            .collect::<String>()
+            .to_uppercase()
+    } else {
+        word.to_uppercase()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::board::{tests as BoardUtils, Coordinate, Direction};
+    use std::io::Cursor;
 
     use super::*;
 
@@ -187,6 +547,345 @@ mod tests {
         );
     }
 
+    #[test]
+    fn battle_trace() {
+        let j = short_dict();
+        let trace = j.battle_trace(&[jolly()], &[fat(), big(), jolly(), folk(), long_xyz()]);
+        assert_eq!(
+            trace,
+            BattleTrace {
+                attackers_valid: vec![true],
+                longest_attacker: 5,
+                defenders_valid: vec![true, true, true, true, false],
+                defenders_too_short: vec![true, true, false, false, false],
+                outcome: Outcome::AttackerWins(vec![0, 1, 4]),
+            }
+        );
+
+        let trace = j.battle_trace(&[xyz(), jolly()], &[big()]);
+        assert_eq!(trace.attackers_valid, vec![false, true]);
+        assert_eq!(trace.outcome, Outcome::DefenderWins);
+    }
+
+    #[test]
+    fn battle_detailed() {
+        let j = short_dict();
+
+        let report = j.battle_detailed(vec![big(), jolly()], vec![xyz(), folk(), big()]);
+        assert_eq!(report.outcome, Outcome::AttackerWins(vec![0, 2]));
+        assert_eq!(report.defeated_words, vec![xyz(), big()]);
+        assert_eq!(report.winning_attacker, Some(jolly()));
+
+        // Equally long attackers are settled by their order, and letters are counted rather than bytes
+        let report = j.battle_detailed(vec![folk(), String::from("ARTS")], vec![xyz()]);
+        assert_eq!(report.winning_attacker, Some(folk()));
+        let accented = Judge::from_reader_normalized("ÉTÉS\nJOLLY\n".as_bytes(), true).unwrap();
+        let report = accented.battle_detailed(vec![String::from("ÉTÉS"), jolly()], vec![xyz()]);
+        assert_eq!(report.winning_attacker, Some(jolly()));
+
+        let report = j.battle_detailed(vec![xyz()], vec![big()]);
+        assert_eq!(report.outcome, Outcome::DefenderWins);
+        assert_eq!(report.defeated_words, Vec::<String>::new());
+        assert_eq!(report.winning_attacker, None);
+    }
+
+    #[test]
+    fn battle_single_matches_battle() {
+        let j = short_dict();
+        let words = [jolly(), word(), xyz(), big(), long_xyz(), folk(), fat()];
+        for attacker in &words {
+            for defender in &words {
+                assert_eq!(
+                    j.battle_single(attacker, defender),
+                    j.battle(vec![attacker.clone()], vec![defender.clone()])
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn has_prefix() {
+        let j = short_dict();
+        assert!(j.has_prefix("JOL"));
+        assert!(j.has_prefix("jolly"));
+        assert!(!j.has_prefix("JOLLYS"));
+        assert!(!j.has_prefix("XY"));
+    }
+
+    #[cfg(feature = "fst")]
+    #[test]
+    fn fst_backend_matches_hash_set() {
+        let fst = Judge::with_backend::<crate::dictionary::FstDictionary>(vec![
+            "BIG", "FAT", "JOLLY", "AND", "SILLY", "FOLK", "ARTS",
+        ]);
+        let j = short_dict();
+        let words = [jolly(), word(), xyz(), big(), long_xyz(), folk(), fat()];
+        for attacker in &words {
+            assert_eq!(fst.valid(attacker), j.valid(attacker));
+            assert_eq!(fst.has_prefix(&attacker[..2]), j.has_prefix(&attacker[..2]));
+            for defender in &words {
+                assert_eq!(
+                    fst.battle(vec![attacker.clone()], vec![defender.clone()]),
+                    j.battle(vec![attacker.clone()], vec![defender.clone()])
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn defense_bonus() {
+        let words = vec!["BIG", "FAT", "ARTS", "JOLLY", "FOLKSY"];
+        let battle = |judge: &Judge, attacker: &str, defender: &str| {
+            judge.battle(vec![String::from(attacker)], vec![String::from(defender)])
+        };
+
+        // A defender only loses to an attacker longer than its own length plus the bonus
+        for bonus in 0..=2 {
+            let judge = Judge::with_defense_bonus(words.clone(), bonus);
+            for attacker in ["FAT", "ARTS", "JOLLY", "FOLKSY"] {
+                let expected = if attacker.len() > "BIG".len() + bonus {
+                    Outcome::AttackerWins(vec![0])
+                } else {
+                    Outcome::DefenderWins
+                };
+                assert_eq!(battle(&judge, attacker, "BIG"), expected);
+                assert_eq!(judge.battle_single(attacker, "BIG"), expected);
+                assert_eq!(
+                    judge
+                        .battle_trace(&[String::from(attacker)], &[String::from("BIG")])
+                        .defenders_too_short,
+                    vec![expected != Outcome::DefenderWins]
+                );
+            }
+        }
+
+        // The default bonus of 1 means an attacker needs two more letters
+        let standard = Judge::new(words.clone());
+        assert_eq!(battle(&standard, "ARTS", "BIG"), Outcome::DefenderWins);
+        assert_eq!(
+            battle(&standard, "JOLLY", "BIG"),
+            Outcome::AttackerWins(vec![0])
+        );
+
+        // Without a bonus, an equal length word loses once ties go to the attacker
+        let level = Judge::with_defense_bonus(words, 0).with_tie_policy(TiePolicy::AttackerWins);
+        assert_eq!(battle(&level, "FAT", "BIG"), Outcome::AttackerWins(vec![0]));
+        assert!(level.weak("BIG", 3));
+    }
+
+    #[test]
+    fn house_rules() {
+        let mut j = short_dict();
+        assert!(!j.contains("QI"));
+        assert!(j.contains("big"));
+
+        j.add_word("qi");
+        assert!(j.contains("QI"));
+        assert!(j.valid("Qi"));
+        assert!(j.has_prefix("Q"));
+
+        assert!(j.remove_word("Big"));
+        assert!(!j.valid("BIG"));
+        assert!(!j.remove_word("BIG"));
+        assert!(!j.remove_word("NOPE"));
+        assert_eq!(j.banned, HashSet::from([String::from("BIG")])); // Only words from the dictionary are banned
+
+        // Removing a house rule word just takes it back out
+        assert!(j.remove_word("QI"));
+        assert!(!j.valid("QI"));
+        assert_eq!(j.banned.len(), 1);
+        // A banned defender is weak, even against an attacker that couldn't otherwise beat it
+        assert_eq!(
+            j.battle(vec![String::from("ARTS")], vec![String::from("BIG")]),
+            Outcome::AttackerWins(vec![0])
+        );
+
+        // Words can be allowed again after being banned
+        j.add_word("BIG");
+        assert!(j.valid("BIG"));
+
+        // Short words can be added, but still aren't valid if the minimum length rules them out
+        let mut strict = Judge::with_min_length(vec![], 2);
+        strict.add_word("A");
+        assert!(strict.contains("A"));
+        assert!(!strict.valid("A"));
+    }
+
+    #[test]
+    fn min_word_length() {
+        let words = vec!["A", "AN", "OF", "BIG"];
+        let battle = |judge: &Judge, attacker: &str, defender: &str| {
+            judge.battle(vec![String::from(attacker)], vec![String::from(defender)])
+        };
+
+        // By default any word in the dictionary counts, however short
+        let lenient = Judge::new(words.clone());
+        assert!(lenient.valid("A"));
+        assert_eq!(battle(&lenient, "A", "Q"), Outcome::AttackerWins(vec![0]));
+
+        // In standard play a single tile is never a valid word, even if it's in the dictionary
+        let standard = Judge::with_min_length(words.clone(), 2);
+        assert!(!standard.valid("A"));
+        assert!(standard.valid("AN"));
+        assert_eq!(battle(&standard, "A", "Q"), Outcome::DefenderWins);
+
+        let strict = Judge::with_min_length(words, 3);
+        assert!(!strict.valid("OF"));
+        assert!(strict.valid("BIG"));
+        assert_eq!(battle(&strict, "BIG", "AN"), Outcome::AttackerWins(vec![0]));
+    }
+
+    #[test]
+    fn tie_policy() {
+        let words = vec!["BIG", "FAT", "JOLLY", "AND", "SILLY", "FOLK", "ARTS"];
+        let standoff = |judge: &Judge| judge.battle(vec![jolly()], vec![jolly()]);
+        let judge = |policy| Judge::with_defense_bonus(words.clone(), 0).with_tie_policy(policy);
+
+        // Without a defender's advantage, equal words are exactly at the threshold
+        assert_eq!(
+            standoff(&judge(TiePolicy::DefenderWins)),
+            Outcome::DefenderWins
+        );
+        assert_eq!(
+            standoff(&judge(TiePolicy::AttackerWins)),
+            Outcome::AttackerWins(vec![0])
+        );
+        assert_eq!(
+            standoff(&judge(TiePolicy::Mutual)),
+            Outcome::Mutual(vec![0])
+        );
+
+        // With the default advantage the threshold is one letter shorter, and longer defenders are never tied
+        let mutual = short_dict().with_tie_policy(TiePolicy::Mutual);
+        assert_eq!(
+            mutual.battle(vec![jolly()], vec![folk()]),
+            Outcome::Mutual(vec![0])
+        );
+        assert_eq!(
+            mutual.battle(vec![jolly()], vec![jolly()]),
+            Outcome::DefenderWins
+        );
+        assert_eq!(
+            mutual.battle(vec![jolly()], vec![big()]),
+            Outcome::AttackerWins(vec![0])
+        );
+        // Weak defenders fall alongside tied ones, and the tie still takes the attackers down
+        assert_eq!(
+            mutual.battle(vec![jolly()], vec![folk(), xyz()]),
+            Outcome::Mutual(vec![0, 1])
+        );
+
+        // The single word fast path agrees under every policy
+        for policy in [
+            TiePolicy::DefenderWins,
+            TiePolicy::AttackerWins,
+            TiePolicy::Mutual,
+        ] {
+            let j = short_dict().with_tie_policy(policy);
+            for defender in [jolly(), folk(), big(), xyz()] {
+                assert_eq!(
+                    j.battle_single(&jolly(), &defender),
+                    j.battle(vec![jolly()], vec![defender.clone()])
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn battle_by_spec_matches_battle() {
+        let j = short_dict();
+        let spec = |words: &[String]| -> Vec<(usize, bool)> {
+            words.iter().map(|w| (w.len(), j.valid(w))).collect()
+        };
+        let battles = [
+            (vec![word()], vec![]),
+            (vec![], vec![word()]),
+            (vec![], vec![]),
+            (vec![xyz()], vec![big()]),
+            (vec![long_xyz()], vec![big()]),
+            (vec![xyz(), jolly()], vec![big()]),
+            (vec![big(), xyz()], vec![big()]),
+            (vec![big()], vec![xyz()]),
+            (vec![big()], vec![big(), xyz()]),
+            (vec![big()], vec![xyz(), big()]),
+            (vec![jolly()], vec![folk()]),
+            (vec![jolly(), big()], vec![folk()]),
+            (vec![jolly()], vec![fat()]),
+            (vec![jolly(), big()], vec![fat()]),
+            (
+                vec![jolly()],
+                vec![fat(), big(), jolly(), folk(), long_xyz()],
+            ),
+        ];
+        for (attackers, defenders) in battles {
+            assert_eq!(
+                Judge::battle_by_spec(&spec(&attackers), &spec(&defenders)),
+                j.battle(attackers, defenders)
+            );
+        }
+        assert_eq!(
+            Judge::battle_by_spec(&[(5, true)], &[(4, true), (3, false), (3, true)]),
+            Outcome::AttackerWins(vec![1, 2])
+        );
+    }
+
+    #[test]
+    fn validate_all() {
+        let j = short_dict();
+        let words = vec![
+            big(),
+            xyz(),
+            jolly(),
+            long_xyz(),
+            String::from("fat"),
+            String::new(),
+        ];
+        assert_eq!(
+            j.validate_all(&words),
+            words.iter().map(|word| j.valid(word)).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            j.validate_all(&words),
+            vec![true, false, true, false, true, false]
+        );
+    }
+
+    #[test]
+    fn strip_diacritics() {
+        let list = "café\nnaïve\n".as_bytes();
+        let stripped = Judge::from_reader_normalized(list, true).unwrap();
+        assert!(stripped.valid("cafe"));
+        assert!(stripped.valid("CAFÉ"));
+        assert!(stripped.valid("NAIVE"));
+        assert!(stripped.valid("cafe\u{301}")); // An e followed by a combining accent
+        assert!(stripped.valid("ÑAÏVỆ")); // Letters outside Latin-1, and with more than one mark
+        assert_eq!(
+            stripped.battle(vec![String::from("CAFÉ")], vec![String::from("XY")]),
+            Outcome::AttackerWins(vec![0])
+        );
+
+        let exact = Judge::from_reader_normalized(list, false).unwrap();
+        assert!(exact.valid("café"));
+        assert!(!exact.valid("cafe"));
+    }
+
+    #[test]
+    fn from_reader() {
+        let j = Judge::from_reader(Cursor::new("big\nFat\n")).unwrap();
+        assert!(j.valid("BIG"));
+        assert!(j.valid("fat"));
+        assert!(!j.valid("AND"));
+        assert_eq!(
+            j.battle(vec![String::from("BIG")], vec![String::from("XY")]),
+            Outcome::AttackerWins(vec![0])
+        );
+
+        assert!(Judge::from_file("./no_such_dictionary.txt").is_err());
+        assert!(Judge::from_file("./dictionary.txt")
+            .unwrap()
+            .valid("ZYZZYVA"));
+    }
+
     #[test]
     fn collins2018() {
         let j = Judge::default();
@@ -218,6 +917,66 @@ mod tests {
         assert_eq!(Judge::winner(&b), Some(0));
     }
 
+    #[test]
+    fn simultaneous_winners() {
+        let b = BoardUtils::from_string(
+            [
+                "X _ _ _ _",
+                "X _ _ _ B",
+                "X _ _ _ B",
+                "X _ _ _ B",
+                "_ _ _ _ B",
+            ]
+            .join("\n"),
+            vec![Coordinate { x: 0, y: 0 }, Coordinate { x: 4, y: 4 }],
+            vec![Direction::North, Direction::South],
+        )
+        .unwrap();
+
+        assert_eq!(Judge::winners(&b), vec![0, 1]);
+        assert_eq!(Judge::winner(&b), None);
+    }
+
+    #[test]
+    fn occupy_quadrants() {
+        let b = BoardUtils::from_string(
+            [
+                "X X X X _ _",
+                "X _ _ _ _ _",
+                "X _ _ _ _ _",
+                "X X X _ B _",
+                "_ _ _ _ B B",
+                "_ _ _ _ B B",
+            ]
+            .join("\n"),
+            vec![Coordinate { x: 0, y: 0 }, Coordinate { x: 5, y: 5 }],
+            vec![Direction::North, Direction::South],
+        )
+        .unwrap();
+
+        // Player 0 holds every quarter but the bottom right, and nobody has reached the far edge
+        assert_eq!(
+            Judge::quadrant_owners(&b),
+            [Some(0), Some(0), Some(1), Some(0)]
+        );
+        assert_eq!(
+            Judge::check_winner(&b, WinCondition::OccupyQuadrants { needed: 3 }),
+            Some(0)
+        );
+        assert_eq!(
+            Judge::check_winner(&b, WinCondition::OccupyQuadrants { needed: 4 }),
+            None
+        );
+        assert_eq!(
+            Judge::check_winner(&b, WinCondition::OccupyQuadrants { needed: 1 }),
+            None
+        );
+        assert_eq!(
+            Judge::check_winner(&b, WinCondition::ReachOppositeEdge),
+            None
+        );
+    }
+
     // Utils
     pub fn short_dict() -> Judge {
         Judge::new(vec!["BIG", "FAT", "JOLLY", "AND", "SILLY", "FOLK", "ARTS"]) // TODO: Collins 2018 list
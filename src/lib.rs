@@ -0,0 +1,12 @@
+pub mod ai;
+pub mod bag;
+pub mod board;
+pub mod dictionary;
+pub mod error;
+pub mod game;
+pub mod hand;
+pub mod history;
+pub mod judge;
+pub mod moves;
+pub mod rules;
+pub mod view;
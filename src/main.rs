@@ -1,15 +1,7 @@
-mod bag;
-mod board;
-mod error;
-mod game;
-mod hand;
-mod judge;
-mod moves;
-
-use board::Coordinate;
-use game::Game;
-use moves::Move;
 use std::io;
+use truncate::board::{self, Coordinate};
+use truncate::game::Game;
+use truncate::moves::Move;
 
 // TODO: this whole file is absolute garbage, clean it up
 
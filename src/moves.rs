@@ -1,8 +1,18 @@
-use super::board::{Board, Coordinate, Square};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::collections::HashSet;
+
+use super::bag::{letter_of, BLANK};
+use super::board::{word_from_squares, Board, Change, Coordinate, Square};
 use super::hand::Hands;
 use super::judge::{Judge, Outcome};
 use crate::error::GamePlayError;
+use crate::rules::CaptureOrder;
+
+// Random playouts give up after this many turns, since random swaps can go on forever
+const PLAYOUT_TURN_LIMIT: usize = 1000;
 
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum Move {
     // TODO: make Move a struct and make player a top level property of it
     Place {
@@ -16,6 +26,36 @@ pub enum Move {
     },
 }
 
+// The consequences of a move, so that callers can show players what happened
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MoveReport {
+    pub outcome: Outcome,
+    pub attacking_words: Vec<String>, // The attacking words that won the battle, empty unless the attacker won
+    pub defeated_squares: Vec<Coordinate>, // Defending squares cleared by a successful attack
+    pub lost_attackers: Vec<(Coordinate, char)>, // Attacking tiles cleared by a failed attack
+    pub truncated_squares: Vec<Coordinate>, // Tiles cleared after the battle because they were cut off from their root
+    pub returned_tiles: Vec<char>, // Every tile that went back into the bag, in the order it went back, with blanks as blanks
+    pub formed_words: Vec<FormedWord>, // The words spelled by the placement, on either axis
+}
+
+// Enough to take back a move exactly: what every square it changed held before, and the hands before it.
+// The hands are kept whole since drawing from the bag reorders it at random, so they can't be rebuilt from the tiles that moved.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UndoEntry {
+    pub played: Move,
+    pub previous_squares: Vec<(Coordinate, Square)>, // The placed square, plus every square cleared by the battle or truncation
+    pub previous_hands: Hands,
+    pub previous_changes: Vec<(Coordinate, Option<Change>)>, // How the changed squares were stamped before, empty unless the board tracks changes
+}
+
+// A word spelled by a placement, and whether it took part in a battle
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FormedWord {
+    pub word: String,
+    pub valid: bool,
+    pub in_battle: bool,
+}
+
 // TODO: is it weird to implement this on Board here rather than on Move?
 impl Board {
     pub fn make_move<'a>(
@@ -24,34 +64,701 @@ impl Board {
         hands: &'a mut Hands,
         judge: &Judge,
     ) -> Result<(), GamePlayError> {
+        self.make_move_reporting(game_move, hands, judge)
+            .map(|_| ())
+    }
+
+    pub fn make_move_reporting<'a>(
+        &'a mut self,
+        game_move: Move,
+        hands: &'a mut Hands,
+        judge: &Judge,
+    ) -> Result<MoveReport, GamePlayError> {
+        self.apply_move(game_move, hands, judge, true)
+    }
+
+    // Makes the move and appends what it takes to undo it to the log
+    pub fn make_move_logged(
+        &mut self,
+        game_move: Move,
+        hands: &mut Hands,
+        judge: &Judge,
+        log: &mut Vec<UndoEntry>,
+    ) -> Result<MoveReport, GamePlayError> {
+        let before = self.clone();
+        let previous_hands = hands.clone();
+        let report = self.make_move_reporting(game_move.clone(), hands, judge)?;
+        let previous_squares = self
+            .changed_since(&before)
+            .expect("Moves don't change the size of the board");
+        let previous_changes = if self.tracks_changes() {
+            previous_squares
+                .iter()
+                .map(|&(position, _)| (position, before.change_at(position)))
+                .collect()
+        } else {
+            vec![]
+        };
+        log.push(UndoEntry {
+            played: game_move,
+            previous_squares,
+            previous_hands,
+            previous_changes,
+        });
+        Ok(report)
+    }
+
+    // Takes back a logged move, which should be the last move made on this board
+    pub fn undo(&mut self, entry: UndoEntry, hands: &mut Hands) -> Result<(), GamePlayError> {
+        for (position, square) in entry.previous_squares {
+            self.restore(position, square)?;
+        }
+        self.unstamp_changes(entry.previous_changes);
+        *hands = entry.previous_hands;
+        Ok(())
+    }
+
+    // Makes the move without resolving any battle, e.g. during a peaceful opening where placing beside an opponent doesn't attack them
+    pub fn make_move_peacefully<'a>(
+        &'a mut self,
+        game_move: Move,
+        hands: &'a mut Hands,
+        judge: &Judge,
+    ) -> Result<MoveReport, GamePlayError> {
+        self.apply_move(game_move, hands, judge, false)
+    }
+
+    // Checks whether the move could be made, with the same errors as making it, but without changing anything.
+    // Battles can't make a move illegal, so the judge isn't consulted.
+    pub fn check_move(
+        &self,
+        game_move: &Move,
+        hands: &Hands,
+        _judge: &Judge,
+    ) -> Result<(), GamePlayError> {
+        // Reject unknown players before anything else looks them up
+        let (Move::Place { player, .. } | Move::Swap { player, .. }) = *game_move;
+        self.get_root(player)?;
+
+        match *game_move {
+            Move::Place {
+                player,
+                tile,
+                position,
+            } => {
+                self.check_placement(player, position)?;
+                hands.check_tile(player, tile)?;
+                if !self.rules().alphabet.contains(&letter_of(tile)) {
+                    return Err(GamePlayError::InvalidLetter { tile });
+                }
+            }
+            Move::Swap { player, positions } => {
+                self.preview_swap(player, positions)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn apply_move(
+        &mut self,
+        game_move: Move,
+        hands: &mut Hands,
+        judge: &Judge,
+        battles: bool,
+    ) -> Result<MoveReport, GamePlayError> {
+        let before = self.tracks_changes().then(|| self.clone());
+        let (Move::Place { player, .. } | Move::Swap { player, .. }) = game_move;
+        let report = self.apply_untracked_move(game_move, hands, judge, battles)?;
+        if let Some(before) = before {
+            self.stamp_changes(&before, player);
+        }
+        Ok(report)
+    }
+
+    fn apply_untracked_move(
+        &mut self,
+        game_move: Move,
+        hands: &mut Hands,
+        judge: &Judge,
+        battles: bool,
+    ) -> Result<MoveReport, GamePlayError> {
+        self.check_move(&game_move, hands, judge)?;
+
         match game_move {
             Move::Place {
                 player,
                 tile,
                 position,
             } => {
-                if let Square::Occupied(..) = self.get(position)? {
-                    return Err(GamePlayError::OccupiedPlace);
+                hands.use_tile(player, tile)?;
+                self.set(position, player, tile)?;
+                if !battles {
+                    return Ok(MoveReport::default());
+                }
+                if self.rules().win_skips_combat && Judge::winner(self) == Some(player) {
+                    return Ok(MoveReport::default());
+                }
+                Ok(self.resolve_attack(player, position, judge, hands))
+            }
+            Move::Swap { player, positions } => {
+                self.swap(player, positions)?;
+                Ok(MoveReport::default())
+            }
+        }
+    }
+
+    // A tile can only be placed on an empty square that is either the player's root, or neighbours one of their tiles
+    fn check_placement(&self, player: usize, position: Coordinate) -> Result<(), GamePlayError> {
+        if let Square::Occupied(..) | Square::Neutral(_) = self.get(position)? {
+            return Err(GamePlayError::OccupiedPlace);
+        }
+
+        if position != self.get_root(player)?
+            && !self
+                .neighbouring_squares(position)
+                .iter()
+                .any(|&(_, square)| match square {
+                    Square::Occupied(p, _, _) => p == player,
+                    _ => false,
+                })
+        {
+            return Err(GamePlayError::NonAdjacentPlace);
+        }
+
+        Ok(())
+    }
+
+    // Every placement the player could make with the tiles in their hand.
+    // They're ordered by row, then column, then letter, so that the order never depends on how the hand happens to be shuffled.
+    pub fn legal_placements(&self, player: usize, hands: &Hands) -> Vec<Move> {
+        // A blank can be played as any letter
+        let mut tiles: Vec<char> = hands
+            .get_hand(player)
+            .iter()
+            .flat_map(|&tile| match tile {
+                BLANK => self
+                    .rules()
+                    .alphabet
+                    .iter()
+                    .flat_map(|letter| letter.to_lowercase())
+                    .collect(),
+                tile => vec![tile],
+            })
+            .collect();
+        tiles.sort_unstable();
+        tiles.dedup();
+
+        let mut placements = Vec::new();
+        for y in 0..self.height() {
+            for x in 0..self.width() {
+                let position = Coordinate { x, y };
+                if self.check_placement(player, position).is_ok() {
+                    placements.extend(tiles.iter().map(|&tile| Move::Place {
+                        player,
+                        tile,
+                        position,
+                    }));
+                }
+            }
+        }
+        placements
+    }
+
+    // Every swap between two of the player's tiles, apart from those on roots
+    pub fn legal_swaps(&self, player: usize) -> Vec<Move> {
+        let owned: Vec<Coordinate> = self
+            .tiles_of(player)
+            .into_iter()
+            .map(|(position, _)| position)
+            .collect();
+
+        let mut swaps = Vec::new();
+        for (i, &first) in owned.iter().enumerate() {
+            for &second in owned.iter().skip(i + 1) {
+                if self.preview_swap(player, [first, second]).is_err() {
+                    continue;
                 }
+                swaps.push(Move::Swap {
+                    player,
+                    positions: [first, second],
+                });
+            }
+        }
+        swaps
+    }
 
-                if position != self.get_root(player)?
-                    && !self.neighbouring_squares(position).iter().any(
-                        |&(_, square)| match square {
-                            Square::Occupied(p, _) => p == player,
-                            _ => false,
-                        },
-                    )
+    // Every move the player could make: each placement, followed by each swap. Both use the same checks as making the move.
+    pub fn legal_moves(&self, player: usize, hands: &Hands) -> Vec<Move> {
+        let mut moves = self.legal_placements(player, hands);
+        moves.extend(self.legal_swaps(player));
+        moves
+    }
+
+    // Placements that neither lose a battle outright, nor leave the new tile open to capture on the opponents' next turn
+    pub fn safe_moves(&self, player: usize, hands: &Hands, judge: &Judge) -> Vec<Move> {
+        self.legal_placements(player, hands)
+            .into_iter()
+            .filter(|placement| {
+                let position = match placement {
+                    Move::Place { position, .. } => *position,
+                    Move::Swap { .. } => unreachable!("legal_placements only returns placements"),
+                };
+                let mut board = self.clone();
+                let mut hands = hands.clone();
+                match board.make_move_reporting(placement.clone(), &mut hands, judge) {
+                    Ok(report) if report.outcome != Outcome::DefenderWins => {
+                        !board.capturable(player, position, &hands, judge)
+                    }
+                    _ => false,
+                }
+            })
+            .collect()
+    }
+
+    // Whether any other player could take the player's tile at the given position with a single placement
+    fn capturable(
+        &self,
+        player: usize,
+        position: Coordinate,
+        hands: &Hands,
+        judge: &Judge,
+    ) -> bool {
+        (0..self.get_orientations().len())
+            .filter(|&opponent| opponent != player)
+            .flat_map(|opponent| self.legal_placements(opponent, hands))
+            .any(|attack| {
+                let mut board = self.clone();
+                let mut hands = hands.clone();
+                board.make_move(attack, &mut hands, judge).is_ok()
+                    && !matches!(board.get(position), Ok(Square::Occupied(owner, _, _)) if owner == player)
+            })
+    }
+
+    // A simple score of the player's position: each of their tiles counts for them, but any tile an opponent could capture
+    // with their next placement counts against them instead
+    pub fn evaluate(&self, player: usize, hands: &Hands, judge: &Judge) -> i32 {
+        let tiles = self.tiles_of(player);
+        let mut exposed = HashSet::new();
+        for opponent in (0..self.get_orientations().len()).filter(|&opponent| opponent != player) {
+            for attack in self.legal_placements(opponent, hands) {
+                let mut board = self.clone();
+                if board.make_move(attack, &mut hands.clone(), judge).is_err() {
+                    continue;
+                }
+                exposed.extend(tiles.iter().map(|&(position, _)| position).filter(|&position| {
+                    !matches!(board.get(position), Ok(Square::Occupied(owner, _, _)) if owner == player)
+                }));
+            }
+        }
+        tiles.len() as i32 - 2 * exposed.len() as i32
+    }
+
+    // Whether every move the player could make leaves them worse off, by `evaluate`, than if they could pass.
+    // A player with no moves at all isn't counted, since they have nothing to choose between.
+    pub fn all_moves_worsen(&self, player: usize, hands: &Hands, judge: &Judge) -> bool {
+        let current = self.evaluate(player, hands, judge);
+        let mut outcomes = self
+            .legal_moves(player, hands)
+            .into_iter()
+            .filter_map(|candidate| {
+                let mut board = self.clone();
+                let mut hands = hands.clone();
+                board
+                    .make_move(candidate, &mut hands, judge)
+                    .ok()
+                    .map(|_| board.evaluate(player, &hands, judge))
+            })
+            .peekable();
+        outcomes.peek().is_some() && outcomes.all(|score| score < current)
+    }
+
+    // Placements by the defender that lengthen the attacker's shortest path to their goal, or cut it off entirely
+    pub fn blocking_moves(
+        &self,
+        defender: usize,
+        attacker: usize,
+        hands: &Hands,
+        judge: &Judge,
+    ) -> Vec<Move> {
+        let distance = match self.distance_to_goal(attacker) {
+            Some(distance) => distance,
+            None => return vec![], // There's no path left to block
+        };
+        self.legal_placements(defender, hands)
+            .into_iter()
+            .filter(|placement| {
+                let mut board = self.clone();
+                let mut hands = hands.clone();
+                board
+                    .make_move(placement.clone(), &mut hands, judge)
+                    .is_ok()
+                    && board
+                        .distance_to_goal(attacker)
+                        .is_none_or(|blocked| blocked > distance)
+            })
+            .collect()
+    }
+
+    // A plain English account of the move, for logs and screen readers, naming the words a placement forms and what its battle would do
+    pub fn describe_move(&self, m: &Move, judge: &Judge) -> String {
+        let describe_square =
+            |position: Coordinate| format!("column {}, row {}", position.x, position.y);
+        let (player, tile, position) = match *m {
+            Move::Place {
+                player,
+                tile,
+                position,
+            } => (player, tile, position),
+            Move::Swap { player, positions } => {
+                return format!(
+                    "Player {} swaps the tiles at {} and {}",
+                    player,
+                    describe_square(positions[0]),
+                    describe_square(positions[1])
+                );
+            }
+        };
+
+        let mut description = format!(
+            "Player {} places {} at {}",
+            player,
+            tile,
+            describe_square(position)
+        );
+        let mut board = self.clone();
+        if board.set(position, player, tile).is_err() {
+            return description;
+        }
+        let (attackers, defenders) = board.collect_combanants(player, position);
+        let outcome = board.judge_battle(&attackers, &defenders, judge);
+        let words = |coordinates: Vec<Vec<Coordinate>>| {
+            let mut words: Vec<String> = vec![];
+            for word in board.word_strings(&coordinates).unwrap_or_default() {
+                if !words.contains(&word) {
+                    words.push(word);
+                }
+            }
+            words.join(", ")
+        };
+        let formed: Vec<Vec<Coordinate>> = attackers
+            .into_iter()
+            .filter(|word| word.len() > 1)
+            .collect();
+        if !formed.is_empty() {
+            description += &format!(", forming {}", words(formed));
+        }
+        let captured =
+            |losers: &[usize]| words(losers.iter().map(|&i| defenders[i].clone()).collect());
+        match &outcome {
+            Outcome::NoBattle => {}
+            Outcome::DefenderWins => {
+                description += &format!(" and failing to capture {}", words(defenders.clone()))
+            }
+            Outcome::AttackerWins(losers) => {
+                description += &format!(" and capturing {}", captured(losers))
+            }
+            Outcome::Mutual(losers) => {
+                description += &format!(
+                    " and capturing {}, losing the attacking tiles too",
+                    captured(losers)
+                )
+            }
+        }
+        description
+    }
+
+    // The longest enemy word the player can capture this turn, along with the placement that captures it.
+    // Mutual battles count as captures, since the defending word is cleared even though the attacking tiles go with it.
+    pub fn top_capture_target(
+        &self,
+        player: usize,
+        hands: &Hands,
+        judge: &Judge,
+    ) -> Option<(Vec<Coordinate>, String, Move)> {
+        let mut best: Option<(Vec<Coordinate>, String, Move)> = None;
+        for placement in self.legal_placements(player, hands) {
+            let (tile, position) = match placement {
+                Move::Place { tile, position, .. } => (tile, position),
+                Move::Swap { .. } => unreachable!("legal_placements only returns placements"),
+            };
+            let mut preview = self.clone();
+            if preview.set(position, player, tile).is_err() {
+                continue;
+            }
+            let (_, defenders) = preview.collect_combanants(player, position);
+
+            let mut board = self.clone();
+            let mut hands = hands.clone();
+            let losers = match board.make_move_reporting(placement.clone(), &mut hands, judge) {
+                Ok(MoveReport {
+                    outcome: Outcome::AttackerWins(losers) | Outcome::Mutual(losers),
+                    ..
+                }) => losers,
+                Ok(MoveReport {
+                    outcome: Outcome::DefenderWins | Outcome::NoBattle,
+                    ..
+                })
+                | Err(_) => continue,
+            };
+            for word in losers.into_iter().filter_map(|loser| defenders.get(loser)) {
+                // Neutral tiles defend too, but they aren't anyone's word
+                if !matches!(preview.get(word[0]), Ok(Square::Occupied(..))) {
+                    continue;
+                }
+                if best
+                    .as_ref()
+                    .is_some_and(|(captured, ..)| captured.len() >= word.len())
                 {
-                    return Err(GamePlayError::NonAdjacentPlace);
+                    continue;
+                }
+                if let Ok(spelled) = word_from_squares(&preview, word) {
+                    best = Some((word.clone(), spelled, placement.clone()));
                 }
+            }
+        }
+        best
+    }
 
-                hands.use_tile(player, tile)?;
-                self.set(position, player, tile)?;
-                self.resolve_attack(player, position, judge, hands);
-                Ok(())
+    // The words the tile at the coordinate is part of, as they're read, along with whether each is valid, e.g. for tooltips
+    pub fn words_through(
+        &self,
+        c: Coordinate,
+        judge: &Judge,
+    ) -> Vec<(Vec<Coordinate>, String, bool)> {
+        let words = self.get_words(c);
+        let spelled = self
+            .word_strings(&words)
+            .expect("Words were just found and should be valid");
+        words
+            .into_iter()
+            .zip(spelled)
+            .map(|(squares, word)| {
+                let valid = judge.valid(&word);
+                (squares, word, valid)
+            })
+            .collect()
+    }
+
+    // Whether placing the tile would complete valid words both vertically and horizontally at once
+    pub fn forms_double_valid_word(
+        &self,
+        player: usize,
+        tile: char,
+        position: Coordinate,
+        judge: &Judge,
+    ) -> bool {
+        if self.check_placement(player, position).is_err() {
+            return false;
+        }
+        let mut board = self.clone();
+        if board.set(position, player, tile).is_err() {
+            return false;
+        }
+        let words = board.get_words(position);
+        words.len() == 2
+            && words.iter().all(|word| word.len() > 1)
+            && board
+                .word_strings(&words)
+                .is_ok_and(|words| words.iter().all(|word| judge.valid(word)))
+    }
+
+    // Whether the player can do anything at all this turn, stopping at the first legal move found
+    pub fn has_legal_move(&self, player: usize, hands: &Hands) -> bool {
+        let can_place = !hands.get_hand(player).is_empty()
+            && (0..self.height()).any(|y| {
+                (0..self.width()).any(|x| self.check_placement(player, Coordinate { x, y }).is_ok())
+            });
+        if can_place {
+            return true;
+        }
+
+        let owned: Vec<Coordinate> = self
+            .tiles_of(player)
+            .into_iter()
+            .map(|(position, _)| position)
+            .collect();
+        owned.iter().enumerate().any(|(i, &first)| {
+            owned
+                .iter()
+                .skip(i + 1)
+                .any(|&second| self.preview_swap(player, [first, second]).is_ok())
+        })
+    }
+
+    // The longest attacking word the player could form by placing any tile from their hand at the position, whether or not it's a real word
+    pub fn max_attacker_length_at(
+        &self,
+        player: usize,
+        position: Coordinate,
+        hands: &Hands,
+    ) -> usize {
+        if self.check_placement(player, position).is_err() {
+            return 0;
+        }
+        let tile = match hands.get_hand(player).first() {
+            Some(&tile) => tile, // Every tile makes words of the same length
+            None => return 0,
+        };
+
+        let mut board = self.clone();
+        board
+            .set(position, player, tile)
+            .expect("The placement was just checked");
+        board
+            .get_words(position)
+            .iter()
+            .map(|word| word.len())
+            .max()
+            .unwrap_or(0)
+    }
+
+    // The defending player's words that, if captured, would open up or shorten the attacking player's path to their goal.
+    // The words most at risk come first, i.e. invalid words, and then shorter words.
+    pub fn goal_defenders(
+        &self,
+        defending_player: usize,
+        attacking_player: usize,
+        judge: &Judge,
+    ) -> Vec<Vec<Coordinate>> {
+        let distance = self.distance_to_goal(attacking_player);
+        let mut seen = HashSet::new(); // Each word is found once from every tile in it
+        let mut words: Vec<Vec<Coordinate>> = vec![];
+        for (position, _) in self.tiles_of(defending_player) {
+            for word in self.get_words(position) {
+                let mut squares = word.clone();
+                squares.sort();
+                if seen.insert(squares) {
+                    words.push(word);
+                }
+            }
+        }
+
+        let mut critical: Vec<(bool, Vec<Coordinate>)> = words
+            .into_iter()
+            .filter(|word| {
+                let mut board = self.clone();
+                for &square in word {
+                    board.clear(square);
+                }
+                match (board.distance_to_goal(attacking_player), distance) {
+                    (Some(_), None) => true,
+                    (Some(opened), Some(current)) => opened < current,
+                    (None, _) => false,
+                }
+            })
+            .map(|word| {
+                let valid = self
+                    .word_strings(std::slice::from_ref(&word))
+                    .is_ok_and(|strings| judge.valid(&strings[0]));
+                (valid, word)
+            })
+            .collect();
+        critical.sort_by_key(|(valid, word)| (*valid, word.len()));
+        critical.into_iter().map(|(_, word)| word).collect()
+    }
+
+    // The number of distinct moves available to the player, which bounds how wide a search from this position must be
+    pub fn branching_factor(&self, player: usize, hands: &Hands) -> usize {
+        self.legal_placements(player, hands).len() + self.legal_swaps(player).len()
+    }
+
+    // The move that leaves the player able to reach the most squares within `depth` further placements, as a cheap measure of mobility.
+    // Ties go to the first move found, and None means the player has no legal move.
+    pub fn best_opening(
+        &self,
+        player: usize,
+        hands: &Hands,
+        judge: &Judge,
+        depth: usize,
+    ) -> Option<Move> {
+        self.legal_moves_ranked(player, hands, judge, |board, _| {
+            board.reachable_within(player, depth).len() as i32
+        })
+        .into_iter()
+        .next()
+        .map(|(best_move, _)| best_move)
+    }
+
+    // Every legal move the player could make, scored by the given evaluation of the position it leads to, best first.
+    // Moves with equal scores keep the order they were generated in, i.e. placements before swaps.
+    pub fn legal_moves_ranked<F: Fn(&Board, &Hands) -> i32>(
+        &self,
+        player: usize,
+        hands: &Hands,
+        judge: &Judge,
+        eval: F,
+    ) -> Vec<(Move, i32)> {
+        let mut ranked: Vec<(Move, i32)> = self
+            .legal_moves(player, hands)
+            .into_iter()
+            .filter_map(|candidate| {
+                let mut board = self.clone();
+                let mut hands = hands.clone();
+                board
+                    .make_move(candidate.clone(), &mut hands, judge)
+                    .ok()
+                    .map(|_| {
+                        let score = eval(&board, &hands);
+                        (candidate, score)
+                    })
+            })
+            .collect();
+        ranked.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
+        ranked
+    }
+
+    // Plays uniformly random legal moves, starting with the given player, until someone wins.
+    // Returns None if every player runs out of moves, or nobody has won within the turn limit.
+    // Tiles are drawn from a copy of the hands' bag that's reseeded from `rng`, so each playout draws its own tiles and the same `rng` replays the same game.
+    pub fn random_playout<R: Rng>(
+        &self,
+        hands: &Hands,
+        judge: &Judge,
+        player: usize,
+        rng: &mut R,
+    ) -> Option<usize> {
+        let players = self.get_orientations().len();
+        let mut board = self.clone();
+        let mut hands = hands.clone();
+        hands.reseed(rng.gen());
+        let mut player = player;
+        let mut passes = 0;
+        for _ in 0..PLAYOUT_TURN_LIMIT {
+            if let Some(winner) = Judge::winner(&board) {
+                return Some(winner);
+            }
+            match board.legal_moves(player, &hands).choose(rng) {
+                Some(chosen) if board.make_move(chosen.clone(), &mut hands, judge).is_ok() => {
+                    passes = 0
+                }
+                _ => {
+                    passes += 1;
+                    if passes == players {
+                        return None;
+                    }
+                }
             }
-            Move::Swap { player, positions } => self.swap(player, positions),
+            player = (player + 1) % players;
+        }
+        Judge::winner(&board)
+    }
+
+    // The fraction of random playouts that the player wins, when it's their turn to move
+    pub fn monte_carlo_value<R: Rng>(
+        &self,
+        hands: &Hands,
+        judge: &Judge,
+        player: usize,
+        rng: &mut R,
+        samples: usize,
+    ) -> f64 {
+        if samples == 0 {
+            return 0.0;
         }
+        let wins = (0..samples)
+            .filter(|_| self.random_playout(hands, judge, player, rng) == Some(player))
+            .count();
+        wins as f64 / samples as f64
     }
 
     // If any attacking word is invalid, or all defending words are valid and stronger than the longest attacking words
@@ -67,70 +774,217 @@ impl Board {
         position: Coordinate,
         judge: &Judge,
         hands: &mut Hands,
-    ) {
+    ) -> MoveReport {
         let (attackers, defenders) = self.collect_combanants(player, position);
-        let attacking_words = self
-            .word_strings(&attackers)
-            .expect("Words were just found and should be valid");
-        let defending_words = self
-            .word_strings(&defenders)
-            .expect("Words were just found and should be valid");
-        match judge.battle(attacking_words, defending_words) {
+        let mut report = MoveReport {
+            outcome: self.judge_battle(&attackers, &defenders, judge),
+            ..MoveReport::default()
+        };
+        // A lone tile counts as a word in battle, but it doesn't spell anything
+        let formed: Vec<Vec<Coordinate>> = attackers
+            .iter()
+            .filter(|word| word.len() > 1)
+            .cloned()
+            .collect();
+        report.formed_words = self
+            .word_strings(&formed)
+            .expect("Words were just found and should be valid")
+            .into_iter()
+            .map(|word| FormedWord {
+                valid: judge.valid(&word),
+                in_battle: report.outcome != Outcome::NoBattle,
+                word,
+            })
+            .collect();
+        match report.outcome.clone() {
             Outcome::NoBattle => {}
             Outcome::DefenderWins => {
-                for word in attackers {
-                    for square in word {
-                        if let Ok(Square::Occupied(_, letter)) = self.get(square) {
-                            hands.return_tile(letter);
-                        }
-                        self.clear(square);
-                    }
-                }
+                self.clear_attackers(player, position, &attackers, hands, &mut report);
             }
             Outcome::AttackerWins(losers) => {
-                for defender_index in losers {
-                    for square in defenders
-                        .get(defender_index)
-                        .expect("Losers should only contain valid squares")
-                    {
-                        if let Ok(Square::Occupied(_, letter)) = self.get(*square) {
-                            hands.return_tile(letter);
-                        }
-                        self.clear(*square);
-                    }
-                }
+                report.attacking_words = self
+                    .word_strings(&attackers)
+                    .expect("Words were just found and should be valid");
+                self.capture_defenders(&losers, &defenders, position, hands, &mut report);
+            }
+            Outcome::Mutual(losers) => {
+                self.capture_defenders(&losers, &defenders, position, hands, &mut report);
+                self.clear_attackers(player, position, &attackers, hands, &mut report);
             }
         }
 
-        self.truncate(hands);
+        for (square, tile) in self.truncate(hands) {
+            report.truncated_squares.push(square);
+            report.returned_tiles.push(tile);
+        }
+        report
     }
 
-    fn collect_combanants(
-        &self,
+    // Clears the attacking words after a lost battle, sending their tiles back to the bag
+    fn clear_attackers(
+        &mut self,
         player: usize,
         position: Coordinate,
-    ) -> (Vec<Vec<Coordinate>>, Vec<Vec<Coordinate>>) {
-        let attackers = self.get_words(position);
-        // Any neighbouring square belonging to another player is attacked. The words containing those squares are the defenders.
-        let defenders = self
-            .neighbouring_squares(position)
+        attackers: &[Vec<Coordinate>],
+        hands: &mut Hands,
+        report: &mut MoveReport,
+    ) {
+        for &square in attackers.iter().flatten() {
+            if self.immune(square) {
+                continue;
+            }
+            if let Ok(attacker @ Square::Occupied(_, letter, _)) = self.get(square) {
+                let tile = attacker.tile().expect("The square holds a tile");
+                if square == position && self.rules().failed_attack_returns_to_hand {
+                    let replacement = hands
+                        .take_back(player, tile)
+                        .expect("The attacker just placed this tile");
+                    report.returned_tiles.extend(replacement);
+                } else {
+                    hands.return_tile(tile);
+                    report.returned_tiles.push(tile);
+                }
+                report.lost_attackers.push((square, letter));
+            }
+            self.clear(square);
+        }
+    }
+
+    // Clears the defeated defending words, in the order the rules give, sending their tiles back to the bag
+    fn capture_defenders(
+        &mut self,
+        losers: &[usize],
+        defenders: &[Vec<Coordinate>],
+        position: Coordinate,
+        hands: &mut Hands,
+        report: &mut MoveReport,
+    ) {
+        let mut losers: Vec<&Vec<Coordinate>> = losers
             .iter()
-            .filter(|(_, square)| match square {
-                Square::Occupied(adjacent_player, _) => player != *adjacent_player,
-                _ => false,
+            .map(|&defender_index| {
+                defenders
+                    .get(defender_index)
+                    .expect("Losers should only contain valid squares")
             })
-            .flat_map(|(position, _)| self.get_words(*position))
             .collect();
-        (attackers, defenders)
-    }
+        self.order_captures(&mut losers, position);
+        for word in losers {
+            for square in word {
+                if self.immune(*square) {
+                    continue;
+                }
+                match self.get(*square) {
+                    Ok(defender @ Square::Occupied(..)) => {
+                        let tile = defender.tile().expect("The square holds a tile");
+                        hands.return_tile(tile);
+                        report.returned_tiles.push(tile);
+                        report.defeated_squares.push(*square);
+                    }
+                    Ok(Square::Neutral(_)) => report.defeated_squares.push(*square), // Neutral tiles never came from the bag
+                    _ => {}
+                }
+                self.clear(*square);
+            }
+        }
+    }
+
+    // Whether the square is a root that battles can't clear under the root immunity rule
+    fn immune(&self, square: Coordinate) -> bool {
+        self.rules().root_immune
+            && (0..self.get_orientations().len()).any(|player| self.get_root(player) == Ok(square))
+    }
+
+    // Whether the player's word along the given squares would hold off an attacking word of the given length
+    pub fn defenders_safe_against(
+        &self,
+        player: usize,
+        defender: &[Coordinate],
+        attacker_length: usize,
+        judge: &Judge,
+    ) -> bool {
+        let owned = defender.iter().all(
+            |&square| matches!(self.get(square), Ok(Square::Occupied(owner, _, _)) if owner == player),
+        );
+        match word_from_squares(self, defender) {
+            Ok(word) if owned => !judge.weak(&word, attacker_length),
+            _ => false,
+        }
+    }
+
+    // Sorts defeated words according to the capture order rule, keeping the battle's order between equals
+    fn order_captures(&self, words: &mut [&Vec<Coordinate>], attack: Coordinate) {
+        let total_distance = |word: &Vec<Coordinate>| -> usize {
+            word.iter()
+                .map(|square| square.x.abs_diff(attack.x) + square.y.abs_diff(attack.y))
+                .sum()
+        };
+        // Compares average distances without dividing
+        let by_distance = |a: &&Vec<Coordinate>, b: &&Vec<Coordinate>| {
+            (total_distance(a) * b.len()).cmp(&(total_distance(b) * a.len()))
+        };
+
+        match self.rules().capture_order {
+            CaptureOrder::NearestFirst => words.sort_by(by_distance),
+            CaptureOrder::FarthestFirst => words.sort_by(|a, b| by_distance(b, a)),
+            CaptureOrder::LongestFirst => words.sort_by_key(|word| std::cmp::Reverse(word.len())),
+        }
+    }
+
+    fn judge_battle(
+        &self,
+        attackers: &[Vec<Coordinate>],
+        defenders: &[Vec<Coordinate>],
+        judge: &Judge,
+    ) -> Outcome {
+        let expect_word = |squares: &[Coordinate]| {
+            word_from_squares(self, squares).expect("Words were just found and should be valid")
+        };
+        match (attackers, defenders) {
+            // Most placements are a single word attacking a single word, which is worth special casing
+            ([attacker], [defender]) => {
+                judge.battle_single(&expect_word(attacker), &expect_word(defender))
+            }
+            _ => judge.battle(
+                attackers.iter().map(|word| expect_word(word)).collect(),
+                defenders.iter().map(|word| expect_word(word)).collect(),
+            ),
+        }
+    }
+
+    fn collect_combanants(
+        &self,
+        player: usize,
+        position: Coordinate,
+    ) -> (Vec<Vec<Coordinate>>, Vec<Vec<Coordinate>>) {
+        let attackers = self.get_words(position);
+        // Any neighbouring square belonging to another player is attacked. The words containing those squares are the defenders.
+        // Each defender is the whole word, even when the attack lands beside the middle of it, so long words aren't judged as weak fragments.
+        // Neutral tiles don't form words, so each one defends alone.
+        let defenders = self
+            .neighbouring_squares(position)
+            .iter()
+            .flat_map(|&(position, square)| match square {
+                Square::Occupied(adjacent_player, _, _) if player != adjacent_player => {
+                    self.get_words(position)
+                }
+                Square::Neutral(_) => vec![vec![position]],
+                _ => vec![],
+            })
+            .collect();
+        (attackers, defenders)
+    }
 }
 
 #[cfg(test)]
-mod tests {
-    use crate::board::{tests as BoardUtils, Direction};
+pub mod tests {
+    use crate::board::{tests as BoardUtils, Change, Direction};
+    use crate::judge::TiePolicy;
+    use crate::rules::Rules;
 
-    use super::super::bag::tests as TileUtils;
+    use super::super::bag::{tests as TileUtils, TileBag};
     use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
 
     pub fn short_dict() -> Judge {
         Judge::new(vec!["BIG", "FAT", "JOLLY", "AND", "SILLY", "FOLK", "ARTS"]) // TODO: Collins 2018 list
@@ -170,128 +1024,1025 @@ mod tests {
             position,
         };
         assert_eq!(
-            b.make_move(dead, &mut hands, &short_dict()),
-            Err(GamePlayError::InvalidPosition { position })
+            b.make_move(dead, &mut hands, &short_dict()),
+            Err(GamePlayError::InvalidPosition { position })
+        );
+    }
+
+    #[test]
+    fn invalid_swaps() {
+        let mut b = Board::new(3, 1);
+        let mut hands = Hands::new(2, 7, TileUtils::trivial_bag());
+        let root = Coordinate { x: 1, y: 0 };
+        let middle = Coordinate { x: 1, y: 1 };
+        let left = Coordinate { x: 0, y: 1 };
+        let right = Coordinate { x: 2, y: 1 };
+        b.set(root, 0, 'A').unwrap();
+        b.set(middle, 0, 'B').unwrap();
+        b.set(Coordinate { x: 1, y: 2 }, 1, 'C').unwrap();
+        b.set(right, 1, 'D').unwrap();
+        let swap = |positions| Move::Swap {
+            player: 0,
+            positions,
+        };
+
+        let cases = [
+            ([middle, middle], GamePlayError::SelfSwap),
+            ([middle, left], GamePlayError::UnoccupiedSwap),
+            ([middle, right], GamePlayError::UnownedSwap),
+        ];
+        for (positions, error) in cases {
+            assert_eq!(
+                b.make_move(swap(positions), &mut hands, &short_dict()),
+                Err(error)
+            );
+        }
+
+        b.set_neutral(left, 'N').unwrap();
+        assert_eq!(
+            b.make_move(swap([middle, left]), &mut hands, &short_dict()),
+            Err(GamePlayError::UnownedSwap)
+        );
+
+        // Roots can never be swapped
+        for positions in [[root, middle], [middle, root]] {
+            assert_eq!(
+                b.make_move(swap(positions), &mut hands, &short_dict()),
+                Err(GamePlayError::RootSwap)
+            );
+        }
+    }
+
+    #[test]
+    fn can_place_and_swap() {
+        let mut b = Board::new(3, 1);
+        let mut hands = Hands::new(1, 7, TileUtils::a_b_bag());
+
+        // Places on the root
+        assert_eq!(
+            b.make_move(
+                Move::Place {
+                    player: 0,
+                    tile: 'A',
+                    position: Coordinate { x: 1, y: 0 }
+                },
+                &mut hands,
+                &short_dict()
+            ),
+            Ok(())
+        );
+        // Can't place on the same place again
+        assert_eq!(
+            b.make_move(
+                Move::Place {
+                    player: 0,
+                    tile: 'A',
+                    position: Coordinate { x: 1, y: 0 }
+                },
+                &mut hands,
+                &short_dict()
+            ),
+            Err(GamePlayError::OccupiedPlace)
+        );
+        // Can't place at a diagonal
+        assert_eq!(
+            b.make_move(
+                Move::Place {
+                    player: 0,
+                    tile: 'A',
+                    position: Coordinate { x: 0, y: 1 }
+                },
+                &mut hands,
+                &short_dict()
+            ),
+            Err(GamePlayError::NonAdjacentPlace)
+        );
+        // Can place directly above
+        assert_eq!(
+            b.make_move(
+                Move::Place {
+                    player: 0,
+                    tile: 'A',
+                    position: Coordinate { x: 1, y: 1 }
+                },
+                &mut hands,
+                &short_dict()
+            ),
+            Ok(())
+        );
+        // Can't place on the same place again
+        assert_eq!(
+            b.make_move(
+                Move::Place {
+                    player: 0,
+                    tile: 'A',
+                    position: Coordinate { x: 1, y: 1 }
+                },
+                &mut hands,
+                &short_dict()
+            ),
+            Err(GamePlayError::OccupiedPlace)
+        );
+
+        // Can't swap the root
+        assert_eq!(
+            b.make_move(
+                Move::Swap {
+                    player: 0,
+                    positions: [Coordinate { x: 1, y: 1 }, Coordinate { x: 1, y: 0 }]
+                },
+                &mut hands,
+                &short_dict()
+            ),
+            Err(GamePlayError::RootSwap)
+        );
+        // Can swap two tiles off the root
+        assert_eq!(
+            b.make_move(
+                Move::Place {
+                    player: 0,
+                    tile: 'B',
+                    position: Coordinate { x: 0, y: 1 }
+                },
+                &mut hands,
+                &short_dict()
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            b.make_move(
+                Move::Swap {
+                    player: 0,
+                    positions: [Coordinate { x: 1, y: 1 }, Coordinate { x: 0, y: 1 }]
+                },
+                &mut hands,
+                &short_dict()
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            b.get(Coordinate { x: 0, y: 1 }),
+            Ok(Square::Occupied(0, 'A', false))
+        );
+    }
+
+    #[test]
+    fn invalid_player_or_tile() {
+        let mut b = Board::new(3, 1);
+        let mut hands = Hands::default();
+
+        assert_eq!(
+            b.make_move(
+                Move::Place {
+                    player: 2,
+                    tile: 'A',
+                    position: Coordinate { x: 1, y: 0 }
+                },
+                &mut hands,
+                &short_dict()
+            ),
+            Err(GamePlayError::NonExistentPlayer { index: 2 })
+        );
+
+        // The player is checked first, even when the rest of the move is also malformed
+        let before = b.clone();
+        assert_eq!(
+            b.make_move(
+                Move::Place {
+                    player: 99,
+                    tile: '&',
+                    position: Coordinate { x: 10, y: 10 }
+                },
+                &mut hands,
+                &short_dict()
+            ),
+            Err(GamePlayError::NonExistentPlayer { index: 99 })
+        );
+        assert_eq!(
+            b.make_move(
+                Move::Swap {
+                    player: 99,
+                    positions: [Coordinate { x: 1, y: 0 }, Coordinate { x: 1, y: 0 }]
+                },
+                &mut hands,
+                &short_dict()
+            ),
+            Err(GamePlayError::NonExistentPlayer { index: 99 })
+        );
+        assert_eq!(b, before);
+
+        assert_eq!(
+            b.make_move(
+                Move::Place {
+                    player: 0,
+                    tile: '&',
+                    position: Coordinate { x: 1, y: 0 }
+                },
+                &mut hands,
+                &short_dict()
+            ),
+            Err(GamePlayError::PlayerDoesNotHaveTile {
+                player: 0,
+                tile: '&'
+            })
+        );
+    }
+
+    #[test]
+    fn alphabet() {
+        // Deal a digit to the first player, and an A to the second
+        let mut bag = TileUtils::trivial_bag();
+        bag.draw_tile();
+        bag.return_tile('1');
+        let mut hands = Hands::new(2, 1, bag);
+        let place = |player, tile, x, y| Move::Place {
+            player,
+            tile,
+            position: Coordinate { x, y },
+        };
+
+        let mut b = Board::new(3, 1);
+        assert_eq!(
+            b.make_move(place(0, '1', 1, 0), &mut hands, &short_dict()),
+            Err(GamePlayError::InvalidLetter { tile: '1' })
+        );
+        assert_eq!(
+            b.make_move(place(1, 'A', 1, 2), &mut hands, &short_dict()),
+            Ok(())
+        );
+
+        let mut b = Board::new(3, 1).with_rules(Rules {
+            alphabet: ['Α', 'Β', 'Γ'].into_iter().collect(),
+            ..Rules::default()
+        });
+        assert_eq!(
+            b.make_move(place(1, 'A', 1, 2), &mut hands, &short_dict()),
+            Err(GamePlayError::InvalidLetter { tile: 'A' })
+        );
+    }
+
+    #[test]
+    fn legal_placements_are_ordered() {
+        let mut b = Board::new(3, 1);
+        b.set(Coordinate { x: 1, y: 0 }, 0, 'A').unwrap();
+        b.set(Coordinate { x: 1, y: 1 }, 0, 'A').unwrap();
+        let mut dist = [0; 26];
+        dist[..3].copy_from_slice(&[1, 1, 1]);
+        let hands = Hands::new(2, 3, TileBag::new(dist)); // Player 0 holds A, B and C in some order
+        let place = |tile, x, y| Move::Place {
+            player: 0,
+            tile,
+            position: Coordinate { x, y },
+        };
+
+        let placements = b.legal_placements(0, &hands);
+        assert_eq!(
+            placements,
+            vec![
+                place('A', 0, 1),
+                place('B', 0, 1),
+                place('C', 0, 1),
+                place('A', 2, 1),
+                place('B', 2, 1),
+                place('C', 2, 1),
+                place('A', 1, 2),
+                place('B', 1, 2),
+                place('C', 1, 2),
+            ]
+        );
+        assert_eq!(b.legal_placements(0, &hands), placements);
+    }
+
+    #[test]
+    fn check_move() {
+        let mut b = Board::new(3, 1);
+        b.set(Coordinate { x: 1, y: 0 }, 0, 'A').unwrap();
+        let hands = Hands::new(2, 7, TileUtils::trivial_bag());
+        let place = |player, tile, x, y| Move::Place {
+            player,
+            tile,
+            position: Coordinate { x, y },
+        };
+        let swap = |x, y| Move::Swap {
+            player: 0,
+            positions: [Coordinate { x: 1, y: 0 }, Coordinate { x, y }],
+        };
+
+        for candidate in [
+            place(0, 'A', 1, 1),
+            place(0, 'A', 10, 10),
+            place(0, 'A', 1, 0),
+            place(0, 'A', 0, 0),
+            place(0, 'A', 2, 2),
+            place(0, 'A', 0, 1),
+            place(0, 'B', 1, 1),
+            place(2, 'A', 1, 1),
+            swap(1, 0),
+            swap(1, 1),
+        ] {
+            let before = (b.clone(), hands.clone());
+            let checked = b.check_move(&candidate, &hands, &short_dict());
+            assert_eq!((b.clone(), hands.clone()), before);
+
+            let mut made = b.clone();
+            let mut made_hands = hands.clone();
+            assert_eq!(
+                checked,
+                made.make_move(candidate, &mut made_hands, &short_dict())
+            );
+        }
+        assert_eq!(
+            b.check_move(&place(0, 'A', 0, 1), &hands, &short_dict()),
+            Err(GamePlayError::NonAdjacentPlace)
+        );
+        assert_eq!(
+            b.check_move(&place(0, 'A', 1, 1), &hands, &short_dict()),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn legal_moves() {
+        let mut b = Board::new(3, 1);
+        b.set(Coordinate { x: 1, y: 0 }, 0, 'A').unwrap();
+        b.set(Coordinate { x: 1, y: 1 }, 0, 'B').unwrap();
+        let hands = Hands::new(2, 7, TileUtils::trivial_bag());
+        let place = |x, y| Move::Place {
+            player: 0,
+            tile: 'A',
+            position: Coordinate { x, y },
+        };
+
+        // The only swap would move the root tile
+        let moves = b.legal_moves(0, &hands);
+        assert_eq!(moves, vec![place(0, 1), place(2, 1), place(1, 2)]);
+        for legal in moves {
+            assert_eq!(b.check_move(&legal, &hands, &short_dict()), Ok(()));
+        }
+    }
+
+    #[test]
+    fn branching_factor() {
+        let mut b = Board::new(3, 1);
+        let hands = Hands::new(2, 7, TileUtils::a_b_bag());
+        let distinct_tiles = {
+            let mut hand = hands.get_hand(0).clone();
+            hand.sort();
+            hand.dedup();
+            hand.len()
+        };
+
+        // Only the root is available on an empty board
+        assert_eq!(b.legal_placements(0, &hands).len(), distinct_tiles);
+        assert_eq!(b.legal_swaps(0), vec![]);
+        assert_eq!(b.branching_factor(0, &hands), distinct_tiles);
+
+        // The root tile can't be swapped
+        b.set(Coordinate { x: 1, y: 0 }, 0, 'A').unwrap();
+        b.set(Coordinate { x: 1, y: 1 }, 0, 'B').unwrap();
+        assert_eq!(b.legal_swaps(0), vec![]);
+        // Either side of the middle tile, and the opponent's root
+        assert_eq!(b.legal_placements(0, &hands).len(), 3 * distinct_tiles);
+        assert_eq!(
+            b.branching_factor(0, &hands),
+            b.legal_placements(0, &hands).len() + b.legal_swaps(0).len()
+        );
+        assert_eq!(b.branching_factor(0, &hands), 3 * distinct_tiles);
+    }
+
+    #[test]
+    fn safe_moves() {
+        let b = BoardUtils::from_string(
+            [
+                "_ _ B _ _",
+                "_ _ I _ _",
+                "_ _ _ _ _",
+                "_ _ _ I _",
+                "_ _ _ B _",
+            ]
+            .join("\n"),
+            vec![Coordinate { x: 2, y: 0 }, Coordinate { x: 3, y: 4 }],
+            vec![Direction::South, Direction::North],
+        )
+        .unwrap();
+        let mut dist = [0; 26];
+        dist[6] = 1;
+        let hands = Hands::new(2, 1, TileBag::new(dist)); // Everyone only has Gs
+        let dict = Judge::new(vec!["BIG"]);
+        let place = |x, y| Move::Place {
+            player: 0,
+            tile: 'G',
+            position: Coordinate { x, y },
+        };
+
+        let safe = b.safe_moves(0, &hands, &dict);
+        // Completing BIG is safe, since the opponent can only attack it with a BIG of their own, which is too short
+        assert!(safe.contains(&place(2, 2)));
+        // As is playing away from the opponent
+        assert!(safe.contains(&place(1, 1)));
+        // But the invalid word IG can be captured when the opponent completes their own BIG beside it
+        assert!(!safe.contains(&place(3, 1)));
+        assert!(b.legal_placements(0, &hands).contains(&place(3, 1)));
+    }
+
+    #[test]
+    fn best_opening() {
+        let b = BoardUtils::from_string(
+            ["_ A _ _ _", "_ _ _ _ _", "_ _ _ _ _", "_ _ _ B _"].join("\n"),
+            vec![Coordinate { x: 1, y: 0 }, Coordinate { x: 3, y: 3 }],
+            vec![Direction::North, Direction::South],
+        )
+        .unwrap();
+        let mut dist = [0; 26];
+        dist[6] = 1;
+        let hands = Hands::new(2, 1, TileBag::new(dist));
+        let place = |x, y| Move::Place {
+            player: 0,
+            tile: 'G',
+            position: Coordinate { x, y },
+        };
+        let reach = |placement: Move, depth| {
+            let mut board = b.clone();
+            board
+                .make_move(placement, &mut hands.clone(), &short_dict())
+                .unwrap();
+            board.reachable_within(0, depth).len()
+        };
+
+        // Heading into the open board keeps more options than tucking into the corner
+        assert_eq!(reach(place(1, 1), 1), 5);
+        assert_eq!(reach(place(2, 0), 1), 4);
+        assert_eq!(reach(place(0, 0), 1), 3);
+        assert!(reach(place(1, 1), 2) > reach(place(0, 0), 2));
+        assert_eq!(
+            b.best_opening(0, &hands, &short_dict(), 2),
+            Some(place(1, 1))
+        );
+
+        let empty_hands = Hands::new(2, 0, TileUtils::trivial_bag());
+        assert_eq!(b.best_opening(0, &empty_hands, &short_dict(), 2), None);
+    }
+
+    #[test]
+    fn all_moves_worsen() {
+        let b = BoardUtils::from_string(
+            ["  G  ", "_ _ _", "_ _ _", "_ I _", "  B  "].join("\n"),
+            vec![Coordinate { x: 1, y: 0 }, Coordinate { x: 1, y: 4 }],
+            vec![Direction::South, Direction::North],
+        )
+        .unwrap();
+        let mut dist = [0; 26];
+        dist[6] = 1;
+        let hands = Hands::new(2, 1, TileBag::new(dist)); // Everyone only has Gs
+        let dict = Judge::new(vec!["BIG"]);
+
+        // Player 0's root is out of reach, but their only placement forms the invalid word GG, which BIG can capture
+        assert_eq!(b.evaluate(0, &hands, &dict), 1);
+        assert_eq!(b.legal_placements(0, &hands).len(), 1);
+        assert!(b.all_moves_worsen(0, &hands, &dict));
+
+        // Once GG is a word it's long enough to hold off BIG, so extending is an improvement
+        let dict = Judge::new(vec!["BIG", "GG"]);
+        assert!(!b.all_moves_worsen(0, &hands, &dict));
+
+        let empty_hands = Hands::new(2, 0, TileUtils::trivial_bag());
+        assert!(!b.all_moves_worsen(0, &empty_hands, &dict));
+    }
+
+    #[test]
+    fn monte_carlo_value() {
+        let b = BoardUtils::from_string(
+            [
+                "_ _ A _ _",
+                "_ _ A _ _",
+                "_ _ A _ _",
+                "  _ _ _ _",
+                "A _ _ _ _",
+            ]
+            .join("\n"),
+            vec![Coordinate { x: 2, y: 0 }, Coordinate { x: 0, y: 4 }],
+            vec![Direction::North, Direction::South],
+        )
+        .unwrap();
+        let hands = Hands::new(2, 7, TileUtils::trivial_bag());
+        let value = |player, seed| {
+            b.monte_carlo_value(
+                &hands,
+                &short_dict(),
+                player,
+                &mut StdRng::seed_from_u64(seed),
+                50,
+            )
+        };
+
+        // The same seed plays out the same games
+        assert_eq!(
+            b.random_playout(&hands, &short_dict(), 0, &mut StdRng::seed_from_u64(1)),
+            b.random_playout(&hands, &short_dict(), 0, &mut StdRng::seed_from_u64(1))
+        );
+        assert_eq!(value(0, 1), value(0, 1));
+
+        // Player 0 is one tile from their goal, while player 1 has barely started
+        assert!(value(0, 1) > 0.7);
+        assert!(value(1, 1) < 0.4);
+        assert_eq!(
+            b.monte_carlo_value(&hands, &short_dict(), 0, &mut StdRng::seed_from_u64(1), 0),
+            0.0
+        );
+
+        // With a real bag, the tiles drawn come from `rng` too, so the state of the hands' own bag doesn't matter
+        let real = Hands::new(2, 7, TileBag::default().with_seed(1));
+        let mut reseeded = real.clone();
+        reseeded.reseed(2);
+        for seed in 0..10 {
+            assert_eq!(
+                b.random_playout(&real, &short_dict(), 1, &mut StdRng::seed_from_u64(seed)),
+                b.random_playout(
+                    &reseeded,
+                    &short_dict(),
+                    1,
+                    &mut StdRng::seed_from_u64(seed)
+                )
+            );
+        }
+        let value = |hands: &Hands| {
+            b.monte_carlo_value(hands, &short_dict(), 0, &mut StdRng::seed_from_u64(1), 50)
+        };
+        assert_eq!(value(&real), value(&reseeded));
+        assert!(value(&real) > 0.5);
+    }
+
+    #[test]
+    fn legal_moves_ranked() {
+        let b = BoardUtils::from_string(
+            [
+                "_ X X _ _",
+                "_ T _ _ _",
+                "_ R _ _ _",
+                "_ _ I _ _",
+                "_ _ T _ _",
+            ]
+            .join("\n"),
+            vec![Coordinate { x: 2, y: 0 }, Coordinate { x: 2, y: 4 }],
+            vec![Direction::North, Direction::South],
+        )
+        .unwrap();
+        let hands = Hands::new(2, 7, TileUtils::trivial_bag());
+        let ranked = b.legal_moves_ranked(0, &hands, &short_dict(), |board, _| {
+            board.tiles_of(0).len() as i32
+        });
+
+        assert_eq!(
+            ranked.len(),
+            b.legal_placements(0, &hands).len() + b.legal_swaps(0).len()
+        );
+        assert!(ranked.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+        // Any placement that doesn't start a battle adds a tile
+        assert_eq!(ranked[0].1, 5);
+        assert!(matches!(ranked[0].0, Move::Place { .. }));
+        // While attacking ITE with the invalid word ARTX loses all but one tile
+        assert_eq!(
+            ranked.last(),
+            Some(&(
+                Move::Place {
+                    player: 0,
+                    tile: 'A',
+                    position: Coordinate { x: 1, y: 3 }
+                },
+                1
+            ))
+        );
+    }
+
+    #[test]
+    fn blocking_moves() {
+        let b = BoardUtils::from_string(
+            [
+                "_ _ A _ _",
+                "_ _ B _ _",
+                "D C E _ F",
+                "_ _ _ _ _",
+                "_ _ G _ _",
+            ]
+            .join("\n"),
+            vec![Coordinate { x: 2, y: 0 }, Coordinate { x: 2, y: 4 }],
+            vec![Direction::North, Direction::South],
+        )
+        .unwrap();
+        let hands = Hands::new(2, 7, TileUtils::trivial_bag());
+        let place = |x, y| Move::Place {
+            player: 0,
+            tile: 'A',
+            position: Coordinate { x, y },
+        };
+
+        // Player 1's only way through player 0's wall is the gap in it, or the square just past it
+        assert_eq!(b.distance_to_goal(1), Some(4));
+        assert_eq!(
+            b.blocking_moves(0, 1, &hands, &short_dict()),
+            vec![place(3, 1), place(3, 2)]
+        );
+
+        let mut walled = b.clone();
+        walled.set(Coordinate { x: 3, y: 2 }, 0, 'A').unwrap();
+        assert_eq!(walled.blocking_moves(0, 1, &hands, &short_dict()), vec![]);
+    }
+
+    #[test]
+    fn describe_move() {
+        let b = BoardUtils::from_string(
+            [
+                "_ _ T _ _",
+                "_ _ A _ _",
+                "_ _ _ _ _",
+                "_ X Y Z _",
+                "_ _ Q _ _",
+            ]
+            .join("\n"),
+            vec![Coordinate { x: 2, y: 0 }, Coordinate { x: 2, y: 4 }],
+            vec![Direction::North, Direction::South],
+        )
+        .unwrap();
+
+        let cat = Move::Place {
+            player: 0,
+            tile: 'C',
+            position: Coordinate { x: 2, y: 2 },
+        };
+
+        // XYZ is long enough to hold off CAT, but YQ isn't a word
+        assert_eq!(
+            b.describe_move(&cat, &Judge::new(vec!["CAT", "XYZ"])),
+            "Player 0 places C at column 2, row 2, forming CAT and capturing YQ"
+        );
+        assert_eq!(
+            b.describe_move(&cat, &Judge::new(vec!["CAT"])),
+            "Player 0 places C at column 2, row 2, forming CAT and capturing YQ, XYZ"
+        );
+        assert_eq!(
+            b.describe_move(&cat, &short_dict()),
+            "Player 0 places C at column 2, row 2, forming CAT and failing to capture YQ, XYZ"
+        );
+        assert_eq!(
+            b.describe_move(
+                &cat,
+                &Judge::new(vec!["CAT", "YQ", "XYZ"]).with_tie_policy(TiePolicy::Mutual)
+            ),
+            "Player 0 places C at column 2, row 2, forming CAT and capturing YQ, losing the attacking tiles too"
+        );
+        assert_eq!(
+            b.describe_move(
+                &Move::Place {
+                    player: 0,
+                    tile: 'A',
+                    position: Coordinate { x: 1, y: 0 },
+                },
+                &short_dict()
+            ),
+            "Player 0 places A at column 1, row 0, forming TA"
+        );
+        assert_eq!(
+            b.describe_move(
+                &Move::Swap {
+                    player: 0,
+                    positions: [Coordinate { x: 2, y: 0 }, Coordinate { x: 2, y: 1 }],
+                },
+                &short_dict()
+            ),
+            "Player 0 swaps the tiles at column 2, row 0 and column 2, row 1"
+        );
+    }
+
+    #[test]
+    fn top_capture_target() {
+        let b = BoardUtils::from_string(
+            [
+                "_ S X _ _",
+                "_ T _ _ _",
+                "_ R _ _ _",
+                "_ _ V _ _",
+                "Y Z W _ _",
+            ]
+            .join("\n"),
+            vec![Coordinate { x: 2, y: 0 }, Coordinate { x: 2, y: 4 }],
+            vec![Direction::North, Direction::South],
+        )
+        .unwrap();
+        let hands = Hands::new(2, 7, TileUtils::trivial_bag());
+
+        // Spelling ARTS captures both VW and YZW, and YZW is worth more
+        assert_eq!(
+            b.top_capture_target(0, &hands, &short_dict()),
+            Some((
+                vec![
+                    Coordinate { x: 0, y: 4 },
+                    Coordinate { x: 1, y: 4 },
+                    Coordinate { x: 2, y: 4 }
+                ],
+                "YZW".to_string(),
+                Move::Place {
+                    player: 0,
+                    tile: 'A',
+                    position: Coordinate { x: 1, y: 3 },
+                }
+            ))
+        );
+        assert_eq!(b.top_capture_target(1, &hands, &short_dict()), None);
+
+        // BIG holds off ARTS exactly, so it's only captured when both sides lose the tie
+        let b = BoardUtils::from_string(
+            [
+                "_ S X _ _",
+                "_ T _ _ _",
+                "_ R _ _ _",
+                "_ _ B _ _",
+                "_ _ I _ _",
+                "_ _ G _ _",
+            ]
+            .join("\n"),
+            vec![Coordinate { x: 2, y: 0 }, Coordinate { x: 2, y: 5 }],
+            vec![Direction::North, Direction::South],
+        )
+        .unwrap();
+        assert_eq!(b.top_capture_target(0, &hands, &short_dict()), None);
+        assert_eq!(
+            b.top_capture_target(0, &hands, &short_dict().with_tie_policy(TiePolicy::Mutual)),
+            Some((
+                (3..=5).map(|y| Coordinate { x: 2, y }).collect(),
+                "BIG".to_string(),
+                Move::Place {
+                    player: 0,
+                    tile: 'A',
+                    position: Coordinate { x: 1, y: 3 },
+                }
+            ))
+        );
+    }
+
+    #[test]
+    fn words_through() {
+        let b = BoardUtils::from_string(
+            [
+                "_ E T _ _",
+                "D N A _ _",
+                "_ _ X _ _",
+                "_ _ _ _ _",
+                "_ _ _ _ _",
+            ]
+            .join("\n"),
+            vec![Coordinate { x: 2, y: 0 }, Coordinate { x: 2, y: 4 }],
+            vec![Direction::North, Direction::South],
+        )
+        .unwrap();
+        let column = |x, ys: [usize; 3]| ys.map(|y| Coordinate { x, y }).to_vec();
+
+        // Player 0 reads upwards and leftwards
+        assert_eq!(
+            b.words_through(Coordinate { x: 2, y: 1 }, &short_dict()),
+            vec![
+                (column(2, [2, 1, 0]), String::from("XAT"), false),
+                (
+                    [2, 1, 0].map(|x| Coordinate { x, y: 1 }).to_vec(),
+                    String::from("AND"),
+                    true
+                ),
+            ]
+        );
+        assert_eq!(
+            b.words_through(Coordinate { x: 4, y: 4 }, &short_dict()),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn forms_double_valid_word() {
+        let crossing = |bottom| {
+            BoardUtils::from_string(
+                [
+                    "_ _ T _ _",
+                    "D N _ _ _",
+                    &format!("_ _ {} _ _", bottom),
+                    "_ _ _ _ _",
+                    "_ _ _ _ _",
+                ]
+                .join("\n"),
+                vec![Coordinate { x: 2, y: 0 }, Coordinate { x: 2, y: 4 }],
+                vec![Direction::North, Direction::South],
+            )
+            .unwrap()
+        };
+        let middle = Coordinate { x: 2, y: 1 };
+
+        // Player 0 reads upwards and leftwards, so an A in the middle spells both FAT and AND
+        assert!(crossing('F').forms_double_valid_word(0, 'A', middle, &short_dict()));
+        assert!(!crossing('F').forms_double_valid_word(0, 'B', middle, &short_dict()));
+
+        // XAT isn't a word, so only AND is valid
+        assert!(!crossing('X').forms_double_valid_word(0, 'A', middle, &short_dict()));
+    }
+
+    #[test]
+    fn has_legal_move() {
+        let mut b = Board::new(3, 1);
+        let empty_hands = Hands::new(2, 0, TileBag::new([0; 26])); // Nothing in hand, and nothing left to draw
+        let hands = Hands::new(2, 7, TileUtils::trivial_bag());
+        assert_eq!(empty_hands.bag().len(), 0);
+
+        assert!(!b.has_legal_move(0, &empty_hands));
+        assert!(b.has_legal_move(0, &hands));
+
+        // Without any tiles, the player can still swap the ones on the board, as long as neither is on the root
+        b.set(Coordinate { x: 1, y: 0 }, 0, 'A').unwrap();
+        assert!(!b.has_legal_move(0, &empty_hands));
+        b.set(Coordinate { x: 1, y: 1 }, 0, 'B').unwrap();
+        assert!(!b.has_legal_move(0, &empty_hands));
+        b.set(Coordinate { x: 0, y: 1 }, 0, 'C').unwrap();
+        assert!(b.has_legal_move(0, &empty_hands));
+        assert!(!b.has_legal_move(1, &empty_hands));
+    }
+
+    #[test]
+    fn max_attacker_length_at() {
+        let b = BoardUtils::from_string(
+            [
+                "_ _ A _ _",
+                "_ _ B _ _",
+                "_ _ C _ _",
+                "_ _ _ _ _",
+                "_ _ _ _ _",
+            ]
+            .join("\n"),
+            vec![Coordinate { x: 2, y: 0 }, Coordinate { x: 2, y: 4 }],
+            vec![Direction::North, Direction::South],
+        )
+        .unwrap();
+        let hands = Hands::new(2, 7, TileUtils::trivial_bag());
+
+        assert_eq!(
+            b.max_attacker_length_at(0, Coordinate { x: 2, y: 3 }, &hands),
+            4
+        );
+        assert_eq!(
+            b.max_attacker_length_at(0, Coordinate { x: 1, y: 1 }, &hands),
+            2
+        );
+        assert_eq!(
+            b.max_attacker_length_at(0, Coordinate { x: 0, y: 4 }, &hands),
+            0
+        );
+        assert_eq!(
+            b.max_attacker_length_at(1, Coordinate { x: 2, y: 4 }, &hands),
+            1
+        );
+
+        let empty_hands = Hands::new(2, 0, TileUtils::trivial_bag());
+        assert_eq!(
+            b.max_attacker_length_at(0, Coordinate { x: 2, y: 3 }, &empty_hands),
+            0
+        );
+    }
+
+    #[test]
+    fn goal_defenders() {
+        let b = BoardUtils::from_string(
+            [
+                "_ _ A _ _",
+                "_ _ _ _ _",
+                "S T O O L",
+                "_ _ _ _ O",
+                "_ _ T O P",
+            ]
+            .join("\n"),
+            vec![Coordinate { x: 2, y: 0 }, Coordinate { x: 2, y: 4 }],
+            vec![Direction::North, Direction::South],
+        )
+        .unwrap();
+        let judge = Judge::new(vec!["STOOL", "TOP"]);
+
+        // The wall is STOOL, which is propped up by the invalid LOP. Losing either opens a path, but TOP is behind the wall.
+        let lop: Vec<Coordinate> = (2..=4).map(|y| Coordinate { x: 4, y }).collect();
+        let stool: Vec<Coordinate> = (0..=4).map(|x| Coordinate { x, y: 2 }).collect();
+        assert_eq!(b.goal_defenders(1, 0, &judge), vec![lop, stool]);
+
+        // Player 0's tiles don't stand in player 1's way
+        assert_eq!(
+            b.goal_defenders(0, 1, &judge),
+            Vec::<Vec<Coordinate>>::new()
         );
     }
 
     #[test]
-    fn can_place_and_swap() {
-        let mut b = Board::new(3, 1);
-        let mut hands = Hands::new(1, 7, TileUtils::a_b_bag());
+    fn scenario_boards() {
+        let root = Coordinate { x: 1, y: 0 };
+        let above_root = Coordinate { x: 1, y: 1 };
+        let mut b = Board::with_initial_tiles(
+            3,
+            2,
+            vec![
+                (root, 0, 'A'),
+                (above_root, 0, 'B'),
+                (Coordinate { x: 1, y: 3 }, 1, 'C'),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            b.to_string(),
+            ["  A  ", "_ B _", "_ _ _", "  C  "].join("\n")
+        );
 
-        // Places on the root
+        // Play continues from the pre-placed tiles
+        let mut hands = Hands::new(2, 7, TileUtils::trivial_bag());
         assert_eq!(
             b.make_move(
                 Move::Place {
                     player: 0,
                     tile: 'A',
-                    position: Coordinate { x: 1, y: 0 }
+                    position: Coordinate { x: 0, y: 1 }
                 },
                 &mut hands,
                 &short_dict()
             ),
             Ok(())
         );
-        // Can't place on the same place again
         assert_eq!(
             b.make_move(
                 Move::Place {
-                    player: 0,
+                    player: 1,
                     tile: 'A',
-                    position: Coordinate { x: 1, y: 0 }
+                    position: Coordinate { x: 1, y: 2 }
                 },
                 &mut hands,
                 &short_dict()
             ),
-            Err(GamePlayError::OccupiedPlace)
+            Ok(())
         );
-        // Can't place at a diagonal
+
+        // Pre-placed tiles must be connected to their owner's root
         assert_eq!(
-            b.make_move(
-                Move::Place {
-                    player: 0,
-                    tile: 'A',
-                    position: Coordinate { x: 0, y: 1 }
-                },
-                &mut hands,
-                &short_dict()
-            ),
+            Board::with_initial_tiles(3, 2, vec![(above_root, 0, 'A')]),
             Err(GamePlayError::NonAdjacentPlace)
         );
-        // Can place directly above
         assert_eq!(
-            b.make_move(
-                Move::Place {
-                    player: 0,
-                    tile: 'A',
-                    position: Coordinate { x: 1, y: 1 }
-                },
-                &mut hands,
-                &short_dict()
-            ),
-            Ok(())
+            Board::with_initial_tiles(3, 2, vec![(root, 1, 'A')]),
+            Err(GamePlayError::NonAdjacentPlace)
         );
-        // Can't place on the same place again
         assert_eq!(
-            b.make_move(
-                Move::Place {
-                    player: 0,
-                    tile: 'A',
-                    position: Coordinate { x: 1, y: 1 }
-                },
-                &mut hands,
-                &short_dict()
-            ),
+            Board::with_initial_tiles(3, 2, vec![(root, 0, 'A'), (root, 0, 'B')]),
             Err(GamePlayError::OccupiedPlace)
         );
-
         assert_eq!(
-            b.make_move(
-                Move::Swap {
-                    player: 0,
-                    positions: [Coordinate { x: 1, y: 1 }, Coordinate { x: 1, y: 0 }]
-                },
-                &mut hands,
-                &short_dict()
-            ),
-            Ok(())
+            Board::with_initial_tiles(3, 2, vec![(root, 2, 'A')]),
+            Err(GamePlayError::NonExistentPlayer { index: 2 })
+        );
+        assert_eq!(
+            Board::with_initial_tiles(3, 2, vec![(Coordinate { x: 0, y: 0 }, 0, 'A')]),
+            Err(GamePlayError::InvalidPosition {
+                position: Coordinate { x: 0, y: 0 }
+            })
         );
     }
 
     #[test]
-    fn invalid_player_or_tile() {
-        let mut b = Board::new(3, 1);
-        let mut hands = Hands::default();
+    fn clones_are_independent() {
+        let original = BoardUtils::from_string(
+            [
+                "_ S X _ _",
+                "_ T _ _ _",
+                "_ R _ _ _",
+                "_ _ I _ _",
+                "_ _ T _ _",
+            ]
+            .join("\n"),
+            vec![Coordinate { x: 2, y: 0 }, Coordinate { x: 2, y: 4 }],
+            vec![Direction::North, Direction::South],
+        )
+        .unwrap();
+        let snapshot = original.to_string();
 
-        assert_eq!(
-            b.make_move(
+        let mut clone = original.clone();
+        clone.set(Coordinate { x: 0, y: 0 }, 0, 'Q').unwrap();
+        clone.clear(Coordinate { x: 1, y: 1 });
+        let mut hands = Hands::new(2, 7, TileUtils::trivial_bag());
+        clone
+            .make_move(
                 Move::Place {
-                    player: 2,
+                    player: 0,
                     tile: 'A',
-                    position: Coordinate { x: 1, y: 0 }
+                    position: Coordinate { x: 2, y: 1 },
                 },
                 &mut hands,
-                &short_dict()
-            ),
-            Err(GamePlayError::NonExistentPlayer { index: 2 })
-        );
+                &short_dict(),
+            )
+            .unwrap();
 
-        assert_eq!(
-            b.make_move(
-                Move::Place {
-                    player: 0,
-                    tile: '&',
-                    position: Coordinate { x: 1, y: 0 }
-                },
-                &mut hands,
-                &short_dict()
-            ),
-            Err(GamePlayError::PlayerDoesNotHaveTile {
-                player: 0,
-                tile: '&'
-            })
-        );
+        assert_ne!(clone, original);
+        assert_eq!(original.to_string(), snapshot);
+
+        // Nor does mutating the original affect an existing clone
+        let mut original = original;
+        let clone = original.clone();
+        original.set(Coordinate { x: 4, y: 4 }, 1, 'Z').unwrap();
+        assert_eq!(clone.to_string(), snapshot);
     }
 
     #[test]
@@ -373,62 +2124,391 @@ mod tests {
             vec![Direction::North, Direction::South],
         )
         .unwrap();
-        one_v_three.set(middle, 0, 'A').unwrap();
+        one_v_three.set(middle, 0, 'A').unwrap();
+
+        assert_eq!(
+            one_v_three.collect_combanants(0, middle),
+            (
+                vec![middle_attacker.clone()],
+                vec![
+                    middle_defender.clone(),
+                    cross_defender.clone(),
+                    right_defender.clone(),
+                    left_defender.clone(),
+                ]
+            )
+        );
+
+        // 2v2
+        let mut two_v_two = BoardUtils::from_string(
+            [
+                "X X M _ _",
+                "X _ D _ _",
+                "L F _ R _",
+                "_ _ M T _",
+                "_ _ D D _",
+            ]
+            .join("\n"),
+            vec![Coordinate { x: 2, y: 0 }, Coordinate { x: 2, y: 4 }],
+            vec![Direction::North, Direction::South],
+        )
+        .unwrap();
+        two_v_two.set(middle, 0, 'A').unwrap();
+        assert_eq!(
+            two_v_two.collect_combanants(0, middle),
+            (
+                vec![middle_attacker, left_attacker],
+                vec![middle_defender, short_cross_defender, right_defender],
+            )
+        );
+    }
+
+    #[test]
+    fn defenders_are_whole_words() {
+        let mut b = BoardUtils::from_string(
+            [
+                "_ _ Y _ _",
+                "_ _ L _ _",
+                "_ _ L _ _",
+                "_ _ O _ _",
+                "_ _ _ _ _",
+                "_ F O L K",
+                "_ _ _ _ S",
+            ]
+            .join("\n"),
+            vec![Coordinate { x: 2, y: 0 }, Coordinate { x: 4, y: 6 }],
+            vec![Direction::North, Direction::South],
+        )
+        .unwrap();
+        let mut dist = [0; 26];
+        dist[9] = 1;
+        let mut hands = Hands::new(2, 1, TileBag::new(dist)); // Everyone only has Js
+        let attack = Coordinate { x: 2, y: 4 };
+        let folk: Vec<Coordinate> = (1..=4).map(|x| Coordinate { x, y: 5 }).collect();
+
+        // JOLLY attacks the middle of FOLK, and would beat the fragment OLK
+        let mut attacked = b.clone();
+        attacked.set(attack, 0, 'J').unwrap();
+        assert_eq!(attacked.collect_combanants(0, attack).1, vec![folk]);
+        assert_eq!(
+            short_dict().battle(vec!["JOLLY".into()], vec!["OLK".into()]),
+            Outcome::AttackerWins(vec![0])
+        );
+
+        // But the whole of FOLK is long enough to hold it off
+        let report = b
+            .make_move_reporting(
+                Move::Place {
+                    player: 0,
+                    tile: 'J',
+                    position: attack,
+                },
+                &mut hands,
+                &short_dict(),
+            )
+            .unwrap();
+        assert_eq!(report.outcome, Outcome::DefenderWins);
+        assert_eq!(
+            b.to_string(),
+            [
+                "_ _ _ _ _",
+                "_ _ _ _ _",
+                "_ _ _ _ _",
+                "_ _ _ _ _",
+                "_ _ _ _ _",
+                "_ F O L K",
+                "_ _ _ _ S",
+            ]
+            .join("\n"),
+        );
+    }
+
+    #[test]
+    fn defenders_safe_against() {
+        let b = BoardUtils::from_string(
+            [
+                "_ _ F _ _",
+                "_ _ O _ _",
+                "_ _ L _ _",
+                "_ _ K _ _",
+                "_ _ _ _ _",
+            ]
+            .join("\n"),
+            vec![Coordinate { x: 2, y: 0 }, Coordinate { x: 2, y: 4 }],
+            vec![Direction::South, Direction::North],
+        )
+        .unwrap();
+        let folk: Vec<Coordinate> = (0..=3).map(|y| Coordinate { x: 2, y }).collect();
+
+        assert!(b.defenders_safe_against(0, &folk, 4, &short_dict()));
+        assert!(b.defenders_safe_against(0, &folk, 5, &short_dict()));
+        assert!(!b.defenders_safe_against(0, &folk, 6, &short_dict()));
+
+        // Invalid words, other players' words, and gaps never hold
+        let klof: Vec<Coordinate> = folk.iter().rev().copied().collect();
+        assert!(!b.defenders_safe_against(0, &klof, 2, &short_dict()));
+        assert!(!b.defenders_safe_against(1, &folk, 2, &short_dict()));
+        let with_gap: Vec<Coordinate> = (0..=4).map(|y| Coordinate { x: 2, y }).collect();
+        assert!(!b.defenders_safe_against(0, &with_gap, 2, &short_dict()));
+    }
+
+    #[test]
+    fn single_word_battles() {
+        let mut b = BoardUtils::from_string(
+            [
+                "_ _ F _ _",
+                "_ _ A _ _",
+                "_ _ _ _ _",
+                "_ _ B _ _",
+                "_ _ G _ _",
+            ]
+            .join("\n"),
+            vec![Coordinate { x: 2, y: 0 }, Coordinate { x: 2, y: 4 }],
+            vec![Direction::North, Direction::South],
+        )
+        .unwrap();
+        let middle = Coordinate { x: 2, y: 2 };
+
+        for dict in [short_dict(), Judge::new(vec!["TAF"]), Judge::new(vec![])] {
+            for tile in ['T', 'X'] {
+                b.set(middle, 0, tile).unwrap();
+                let (attackers, defenders) = b.collect_combanants(0, middle);
+                assert_eq!((attackers.len(), defenders.len()), (1, 1));
+                assert_eq!(
+                    b.judge_battle(&attackers, &defenders, &dict),
+                    dict.battle(
+                        b.word_strings(&attackers).unwrap(),
+                        b.word_strings(&defenders).unwrap()
+                    )
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn resolve_successful_attack() {
+        let mut b = BoardUtils::from_string(
+            [
+                "_ S X _ _",
+                "_ T _ _ _",
+                "_ R _ _ _",
+                "_ _ I _ _",
+                "_ _ T _ _",
+            ]
+            .join("\n"),
+            vec![Coordinate { x: 2, y: 0 }, Coordinate { x: 2, y: 4 }],
+            vec![Direction::North, Direction::South],
+        )
+        .unwrap();
+        let mut hands = Hands::new(2, 7, TileUtils::trivial_bag());
+
+        let report = b
+            .make_move_reporting(
+                Move::Place {
+                    player: 0,
+                    tile: 'A',
+                    position: Coordinate { x: 1, y: 3 },
+                },
+                &mut hands,
+                &short_dict(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            b.to_string(),
+            [
+                "_ S X _ _",
+                "_ T _ _ _",
+                "_ R _ _ _",
+                "_ A _ _ _",
+                "_ _ _ _ _",
+            ]
+            .join("\n"),
+        );
+        assert_eq!(
+            report,
+            MoveReport {
+                outcome: Outcome::AttackerWins(vec![0]),
+                attacking_words: vec![String::from("ARTS")],
+                defeated_squares: vec![Coordinate { x: 2, y: 3 }, Coordinate { x: 2, y: 4 }],
+                lost_attackers: vec![],
+                truncated_squares: vec![],
+                returned_tiles: vec!['I', 'T'],
+                formed_words: vec![FormedWord {
+                    word: String::from("ARTS"),
+                    valid: true,
+                    in_battle: true,
+                }],
+            }
+        );
+    }
+
+    #[test]
+    fn reports_truncated_tiles() {
+        let board = BoardUtils::from_string(
+            [
+                "_ S X _ _",
+                "_ T _ _ _",
+                "_ R _ _ _",
+                "_ _ I _ _",
+                "_ _ T Q _",
+            ]
+            .join("\n"),
+            vec![Coordinate { x: 2, y: 0 }, Coordinate { x: 2, y: 4 }],
+            vec![Direction::North, Direction::South],
+        )
+        .unwrap();
+        let attack = Move::Place {
+            player: 0,
+            tile: 'A',
+            position: Coordinate { x: 1, y: 3 },
+        };
 
+        // Capturing IT takes player 1's root with it, which cuts Q off
+        let mut b = board.clone();
+        let mut hands = Hands::new(2, 7, TileUtils::trivial_bag());
+        let report = b
+            .make_move_reporting(attack.clone(), &mut hands, &short_dict())
+            .unwrap();
+        assert_eq!(report.outcome, Outcome::AttackerWins(vec![0]));
         assert_eq!(
-            one_v_three.collect_combanants(0, middle),
-            (
-                vec![middle_attacker.clone()],
-                vec![
-                    middle_defender.clone(),
-                    cross_defender.clone(),
-                    right_defender.clone(),
-                    left_defender.clone(),
-                ]
-            )
+            report.defeated_squares,
+            vec![Coordinate { x: 2, y: 3 }, Coordinate { x: 2, y: 4 }]
         );
+        assert_eq!(report.truncated_squares, vec![Coordinate { x: 3, y: 4 }]);
+        assert_eq!(report.returned_tiles, vec!['I', 'T', 'Q']);
 
-        // 2v2
-        let mut two_v_two = BoardUtils::from_string(
+        // With an immune root only I is captured, so Q stays connected
+        let mut b = board.with_rules(Rules {
+            root_immune: true,
+            ..Rules::default()
+        });
+        let mut hands = Hands::new(2, 7, TileUtils::trivial_bag());
+        let report = b
+            .make_move_reporting(attack, &mut hands, &short_dict())
+            .unwrap();
+        assert_eq!(report.outcome, Outcome::AttackerWins(vec![0]));
+        assert_eq!(report.defeated_squares, vec![Coordinate { x: 2, y: 3 }]);
+        assert_eq!(report.truncated_squares, vec![]);
+    }
+
+    #[test]
+    fn undo_logged_moves() {
+        let board = BoardUtils::from_string(
             [
-                "X X M _ _",
-                "X _ D _ _",
-                "L F _ R _",
-                "_ _ M T _",
-                "_ _ D D _",
+                "_ S X _ _",
+                "_ T _ _ _",
+                "_ R _ _ _",
+                "_ _ I _ _",
+                "_ _ T Q _",
             ]
             .join("\n"),
             vec![Coordinate { x: 2, y: 0 }, Coordinate { x: 2, y: 4 }],
             vec![Direction::North, Direction::South],
         )
         .unwrap();
-        two_v_two.set(middle, 0, 'A').unwrap();
-        assert_eq!(
-            two_v_two.collect_combanants(0, middle),
-            (
-                vec![middle_attacker, left_attacker],
-                vec![middle_defender, short_cross_defender, right_defender],
+        let hands = Hands::new(2, 7, TileUtils::trivial_bag());
+        let mut log = vec![];
+
+        // A winning attack captures IT, truncates Q, and sends all three tiles to the bag
+        let mut b = board.clone();
+        let mut h = hands.clone();
+        let report = b
+            .make_move_logged(
+                Move::Place {
+                    player: 0,
+                    tile: 'A',
+                    position: Coordinate { x: 1, y: 3 },
+                },
+                &mut h,
+                &short_dict(),
+                &mut log,
+            )
+            .unwrap();
+        assert!(matches!(report.outcome, Outcome::AttackerWins(_)));
+        assert_eq!(report.returned_tiles, vec!['I', 'T', 'Q']);
+        assert_eq!(log.len(), 1);
+        assert_eq!(log[0].previous_squares.len(), 4);
+
+        b.undo(log.pop().unwrap(), &mut h).unwrap();
+        assert_eq!(b.to_string(), board.to_string());
+        assert_eq!(b, board);
+        assert_eq!(h, hands);
+
+        // Failed moves aren't logged
+        assert!(b
+            .make_move_logged(
+                Move::Place {
+                    player: 0,
+                    tile: 'A',
+                    position: Coordinate { x: 4, y: 4 },
+                },
+                &mut h,
+                &short_dict(),
+                &mut log,
             )
+            .is_err());
+        assert_eq!(log, vec![]);
+
+        // Undoing also takes back the stamps of a board that tracks changes
+        let tracked = board.with_change_tracking();
+        let mut b = tracked.clone();
+        let mut h = hands.clone();
+        let moves = [
+            Move::Place {
+                player: 0,
+                tile: 'A',
+                position: Coordinate { x: 1, y: 3 },
+            },
+            Move::Swap {
+                player: 0,
+                positions: [Coordinate { x: 1, y: 0 }, Coordinate { x: 1, y: 1 }],
+            },
+        ];
+        let mut boards = vec![];
+        for played in moves {
+            boards.push(b.clone());
+            b.make_move_logged(played, &mut h, &short_dict(), &mut log)
+                .unwrap();
+        }
+        assert_eq!(b.turn(), Some(2));
+        assert_eq!(
+            b.change_at(Coordinate { x: 1, y: 0 }),
+            Some(Change { turn: 1, player: 0 })
         );
+
+        b.undo(log.pop().unwrap(), &mut h).unwrap();
+        assert_eq!(b.turn(), Some(1));
+        assert_eq!(b.change_at(Coordinate { x: 1, y: 0 }), None);
+        assert_eq!(b, boards[1]);
+        b.undo(log.pop().unwrap(), &mut h).unwrap();
+        assert_eq!(b.recently_changed(0), vec![]);
+        assert_eq!(b, boards[0]);
+        assert_eq!(b, tracked);
+        assert_eq!(h, hands);
     }
 
     #[test]
-    fn resolve_successful_attack() {
+    fn stamps_changed_squares() {
         let mut b = BoardUtils::from_string(
             [
                 "_ S X _ _",
                 "_ T _ _ _",
                 "_ R _ _ _",
                 "_ _ I _ _",
-                "_ _ T _ _",
+                "_ _ T Q _",
             ]
             .join("\n"),
             vec![Coordinate { x: 2, y: 0 }, Coordinate { x: 2, y: 4 }],
             vec![Direction::North, Direction::South],
         )
-        .unwrap();
+        .unwrap()
+        .with_change_tracking();
         let mut hands = Hands::new(2, 7, TileUtils::trivial_bag());
+        assert_eq!(b.turn(), Some(0));
+        assert_eq!(b.recently_changed(0), vec![]);
 
+        // The placement, the captured IT, and the truncated Q all change on turn 0
         b.make_move(
             Move::Place {
                 player: 0,
@@ -439,18 +2519,161 @@ mod tests {
             &short_dict(),
         )
         .unwrap();
-
+        b.make_move(
+            Move::Swap {
+                player: 0,
+                positions: [Coordinate { x: 1, y: 0 }, Coordinate { x: 1, y: 1 }],
+            },
+            &mut hands,
+            &short_dict(),
+        )
+        .unwrap();
+        assert_eq!(b.turn(), Some(2));
         assert_eq!(
-            b.to_string(),
+            b.change_at(Coordinate { x: 3, y: 4 }),
+            Some(Change { turn: 0, player: 0 })
+        );
+        assert_eq!(
+            b.recently_changed(0),
+            vec![
+                Coordinate { x: 1, y: 0 },
+                Coordinate { x: 1, y: 1 },
+                Coordinate { x: 1, y: 3 },
+                Coordinate { x: 2, y: 3 },
+                Coordinate { x: 2, y: 4 },
+                Coordinate { x: 3, y: 4 },
+            ]
+        );
+        assert_eq!(
+            b.recently_changed(1),
+            vec![Coordinate { x: 1, y: 0 }, Coordinate { x: 1, y: 1 }]
+        );
+        assert_eq!(b.recently_changed(2), vec![]);
+
+        // Illegal moves don't use up a turn
+        assert!(b
+            .make_move(
+                Move::Place {
+                    player: 0,
+                    tile: 'A',
+                    position: Coordinate { x: 1, y: 3 },
+                },
+                &mut hands,
+                &short_dict(),
+            )
+            .is_err());
+        assert_eq!(b.turn(), Some(2));
+
+        // Untracked boards have nothing to report
+        assert_eq!(Board::new(3, 3).recently_changed(0), vec![]);
+        assert_eq!(Board::new(3, 3).turn(), None);
+    }
+
+    #[test]
+    fn tie_policies() {
+        // ARTS attacks BIG, which is exactly long enough to hold it off
+        let board = BoardUtils::from_string(
             [
                 "_ S X _ _",
                 "_ T _ _ _",
                 "_ R _ _ _",
-                "_ A _ _ _",
-                "_ _ _ _ _",
+                "_ _ B _ _",
+                "_ _ I _ _",
+                "_ _ G _ _",
             ]
             .join("\n"),
+            vec![Coordinate { x: 2, y: 0 }, Coordinate { x: 2, y: 5 }],
+            vec![Direction::North, Direction::South],
+        )
+        .unwrap();
+        let attacker = Coordinate { x: 1, y: 3 };
+        let defender = Coordinate { x: 2, y: 3 };
+        let cases = [
+            (
+                TiePolicy::DefenderWins,
+                Outcome::DefenderWins,
+                Square::Empty,
+                Square::Occupied(1, 'B', false),
+            ),
+            (
+                TiePolicy::AttackerWins,
+                Outcome::AttackerWins(vec![0]),
+                Square::Occupied(0, 'A', false),
+                Square::Empty,
+            ),
+            (
+                TiePolicy::Mutual,
+                Outcome::Mutual(vec![0]),
+                Square::Empty,
+                Square::Empty,
+            ),
+        ];
+        for (policy, outcome, attacker_square, defender_square) in cases {
+            let mut b = board.clone();
+            let mut hands = Hands::new(2, 7, TileUtils::trivial_bag());
+            let report = b
+                .make_move_reporting(
+                    Move::Place {
+                        player: 0,
+                        tile: 'A',
+                        position: attacker,
+                    },
+                    &mut hands,
+                    &short_dict().with_tie_policy(policy),
+                )
+                .unwrap();
+            assert_eq!(report.outcome, outcome);
+            assert_eq!(b.get(attacker), Ok(attacker_square));
+            assert_eq!(b.get(defender), Ok(defender_square));
+        }
+    }
+
+    #[test]
+    fn reports_formed_words() {
+        let mut b = BoardUtils::from_string(
+            [
+                "_ E T _ _",
+                "D N _ Q Q",
+                "_ E X _ Q",
+                "_ _ _ _ Q",
+                "_ _ _ _ Q",
+            ]
+            .join("\n"),
+            vec![Coordinate { x: 2, y: 0 }, Coordinate { x: 4, y: 4 }],
+            vec![Direction::North, Direction::South],
         )
+        .unwrap();
+        let mut hands = Hands::new(2, 7, TileUtils::trivial_bag());
+        let place = |x, y| Move::Place {
+            player: 0,
+            tile: 'A',
+            position: Coordinate { x, y },
+        };
+        let formed = |word: &str, valid, in_battle| FormedWord {
+            word: String::from(word),
+            valid,
+            in_battle,
+        };
+
+        // Away from player 1, DA and TEA are formed but don't fight
+        let report = b
+            .make_move_reporting(place(0, 0), &mut hands, &short_dict())
+            .unwrap();
+        assert_eq!(report.outcome, Outcome::NoBattle);
+        assert_eq!(
+            report.formed_words,
+            vec![formed("DA", false, false), formed("TEA", false, false)]
+        );
+
+        // Crossing in the middle spells XAT upwards and AND leftwards, and both attack QQ
+        let report = b
+            .make_move_reporting(place(2, 1), &mut hands, &short_dict())
+            .unwrap();
+        assert_eq!(report.outcome, Outcome::DefenderWins);
+        assert_eq!(
+            report.formed_words,
+            vec![formed("XAT", false, true), formed("AND", true, true)]
+        );
     }
 
     #[test]
@@ -470,16 +2693,17 @@ mod tests {
         .unwrap();
         let mut hands = Hands::new(2, 7, TileUtils::trivial_bag());
 
-        b.make_move(
-            Move::Place {
-                player: 0,
-                tile: 'A',
-                position: Coordinate { x: 1, y: 3 },
-            },
-            &mut hands,
-            &short_dict(),
-        )
-        .unwrap();
+        let report = b
+            .make_move_reporting(
+                Move::Place {
+                    player: 0,
+                    tile: 'A',
+                    position: Coordinate { x: 1, y: 3 },
+                },
+                &mut hands,
+                &short_dict(),
+            )
+            .unwrap();
 
         assert_eq!(
             b.to_string(),
@@ -491,7 +2715,332 @@ mod tests {
                 "_ _ T _ _",
             ]
             .join("\n"),
+        );
+        assert_eq!(report.outcome, Outcome::DefenderWins);
+        assert_eq!(
+            report.lost_attackers,
+            vec![
+                (Coordinate { x: 1, y: 3 }, 'A'),
+                (Coordinate { x: 1, y: 2 }, 'R'),
+                (Coordinate { x: 1, y: 1 }, 'T'),
+                (Coordinate { x: 1, y: 0 }, 'X'),
+            ]
+        );
+        assert_eq!(report.defeated_squares, vec![]);
+    }
+
+    #[test]
+    fn blanks() {
+        let attacking = |top_right| {
+            BoardUtils::from_string(
+                [
+                    &format!("_ {} X _ _", top_right),
+                    "_ T _ _ _",
+                    "_ R _ _ _",
+                    "_ _ I _ _",
+                    "_ _ T _ _",
+                ]
+                .join("\n"),
+                vec![Coordinate { x: 2, y: 0 }, Coordinate { x: 2, y: 4 }],
+                vec![Direction::North, Direction::South],
+            )
+            .unwrap()
+        };
+        let blank_hands = || Hands::new(2, 7, TileBag::new([0; 26]).with_blanks(1));
+        let place = |tile| Move::Place {
+            player: 0,
+            tile,
+            position: Coordinate { x: 1, y: 3 },
+        };
+        let position = Coordinate { x: 1, y: 3 };
+
+        // The blank has to be played as a particular letter, which is shown in lowercase
+        let mut b = attacking('S');
+        let mut hands = blank_hands();
+        assert_eq!(
+            b.make_move(place('A'), &mut hands, &short_dict()),
+            Err(GamePlayError::PlayerDoesNotHaveTile {
+                player: 0,
+                tile: 'A'
+            })
+        );
+        assert_eq!(
+            b.make_move(place(BLANK), &mut hands, &short_dict()),
+            Err(GamePlayError::InvalidLetter { tile: BLANK })
+        );
+        let report = b
+            .make_move_reporting(place('a'), &mut hands, &short_dict())
+            .unwrap();
+        assert_eq!(b.get(position), Ok(Square::Occupied(0, 'A', true)));
+        assert_eq!(b.get(position).unwrap().to_string(), "a");
+        assert_eq!(report.attacking_words, vec![String::from("ARTS")]);
+        assert_eq!(report.outcome, Outcome::AttackerWins(vec![0]));
+        assert_eq!(hands.get_hand(0), &vec![BLANK; 7]);
+
+        // A blank that loses its battle goes back in the bag as a blank
+        let mut b = attacking('X');
+        let mut hands = blank_hands();
+        let report = b
+            .make_move_reporting(place('a'), &mut hands, &short_dict())
+            .unwrap();
+        assert_eq!(report.outcome, Outcome::DefenderWins);
+        assert_eq!(report.returned_tiles, vec![BLANK, 'R', 'T', 'X']);
+        assert!(hands.bag().remaining_counts().contains_key(&BLANK));
+    }
+
+    #[test]
+    fn failed_attack_returns_to_hand() {
+        let board = BoardUtils::from_string(
+            [
+                "_ X _ _ _",
+                "_ _ _ _ _",
+                "_ _ _ _ _",
+                "_ _ I _ _",
+                "_ _ T _ _",
+            ]
+            .join("\n"),
+            vec![Coordinate { x: 1, y: 0 }, Coordinate { x: 2, y: 4 }],
+            vec![Direction::North, Direction::South],
+        )
+        .unwrap();
+        let attack = || {
+            (0..3)
+                .map(|y| Move::Place {
+                    player: 0,
+                    tile: 'A',
+                    position: Coordinate { x: 1, y: y + 1 },
+                })
+                .collect::<Vec<_>>()
+        };
+
+        for rule in [false, true] {
+            let mut b = board.clone().with_rules(Rules {
+                failed_attack_returns_to_hand: rule,
+                ..Rules::default()
+            });
+            // Everyone holds an A and the only tile left to draw is a Q
+            let mut hands = Hands::new(2, 1, TileUtils::trivial_bag());
+            let moves = attack();
+            b.make_move(moves[0].clone(), &mut hands, &short_dict())
+                .unwrap();
+            b.make_move(moves[1].clone(), &mut hands, &short_dict())
+                .unwrap();
+            hands.return_tile('Q');
+
+            // XAAA attacks IT and loses
+            let report = b
+                .make_move_reporting(moves[2].clone(), &mut hands, &short_dict())
+                .unwrap();
+            assert_eq!(report.outcome, Outcome::DefenderWins);
+            assert_eq!(b.get(Coordinate { x: 1, y: 3 }), Ok(Square::Empty));
+            if rule {
+                // The Q drawn to replace the attacking A goes back in the bag instead
+                assert_eq!(hands.get_hand(0), &vec!['A']);
+                assert_eq!(report.returned_tiles, vec!['Q', 'A', 'A', 'X']);
+            } else {
+                assert_eq!(hands.get_hand(0), &vec!['Q']);
+                assert_eq!(report.returned_tiles, vec!['A', 'A', 'A', 'X']);
+            }
+        }
+    }
+
+    #[test]
+    fn root_immunity() {
+        let board = BoardUtils::from_string(
+            [
+                "_ S X _ _",
+                "_ T _ _ _",
+                "_ R _ _ _",
+                "_ _ I _ _",
+                "_ _ T _ _",
+                "_ _ E _ _",
+            ]
+            .join("\n"),
+            vec![Coordinate { x: 2, y: 0 }, Coordinate { x: 2, y: 5 }],
+            vec![Direction::North, Direction::South],
+        )
+        .unwrap();
+        let attack = Move::Place {
+            player: 0,
+            tile: 'A',
+            position: Coordinate { x: 1, y: 3 },
+        };
+        let root = Coordinate { x: 2, y: 5 };
+
+        // ARTS captures the whole of ITE, including the defender's root
+        let mut b = board.clone();
+        let mut hands = Hands::new(2, 7, TileUtils::trivial_bag());
+        let report = b
+            .make_move_reporting(attack.clone(), &mut hands, &short_dict())
+            .unwrap();
+        assert_eq!(report.outcome, Outcome::AttackerWins(vec![0]));
+        assert_eq!(b.get(root), Ok(Square::Empty));
+
+        let mut b = board.with_rules(Rules {
+            root_immune: true,
+            ..Rules::default()
+        });
+        let report = b
+            .make_move_reporting(attack, &mut hands, &short_dict())
+            .unwrap();
+        assert_eq!(report.outcome, Outcome::AttackerWins(vec![0]));
+        assert_eq!(
+            report.defeated_squares,
+            vec![Coordinate { x: 2, y: 3 }, Coordinate { x: 2, y: 4 }]
+        );
+        assert_eq!(b.get(root), Ok(Square::Occupied(1, 'E', false)));
+    }
+
+    #[test]
+    fn capture_order() {
+        let board = |capture_order| {
+            BoardUtils::from_string(
+                [
+                    "_ _ M _ _",
+                    "_ _ D _ _",
+                    "_ L _ R _",
+                    "_ F M T _",
+                    "_ D D D _",
+                ]
+                .join("\n"),
+                vec![Coordinate { x: 2, y: 0 }, Coordinate { x: 2, y: 4 }],
+                vec![Direction::North, Direction::South],
+            )
+            .unwrap()
+            .with_rules(Rules {
+                capture_order,
+                ..Rules::default()
+            })
+        };
+        let c = |x, y| Coordinate { x, y };
+
+        let mut results = vec![];
+        for (capture_order, expected) in [
+            (
+                CaptureOrder::NearestFirst, // The default
+                vec![
+                    c(2, 3),
+                    c(2, 4),
+                    c(1, 3),
+                    c(3, 3),
+                    c(3, 2),
+                    c(3, 4),
+                    c(1, 2),
+                    c(1, 4),
+                ],
+            ),
+            (
+                CaptureOrder::FarthestFirst,
+                vec![
+                    c(3, 2),
+                    c(3, 3),
+                    c(3, 4),
+                    c(1, 2),
+                    c(1, 3),
+                    c(1, 4),
+                    c(2, 3),
+                    c(2, 4),
+                ],
+            ),
+            (
+                CaptureOrder::LongestFirst,
+                vec![
+                    c(1, 3),
+                    c(2, 3),
+                    c(3, 3),
+                    c(3, 2),
+                    c(3, 4),
+                    c(1, 2),
+                    c(1, 4),
+                    c(2, 4),
+                ],
+            ),
+        ] {
+            let mut b = board(capture_order);
+            let mut hands = Hands::new(2, 7, TileUtils::trivial_bag());
+            let report = b
+                .make_move_reporting(
+                    Move::Place {
+                        player: 0,
+                        tile: 'A',
+                        position: c(2, 2),
+                    },
+                    &mut hands,
+                    &Judge::new(vec!["ADM"]),
+                )
+                .unwrap();
+            assert_eq!(report.defeated_squares, expected);
+            results.push(b);
+        }
+        assert_eq!(Rules::default().capture_order, CaptureOrder::NearestFirst);
+
+        // The order doesn't change the outcome
+        assert_eq!(results[0].to_string(), results[1].to_string());
+        assert_eq!(results[0].to_string(), results[2].to_string());
+    }
+
+    #[test]
+    fn capture_neutral_tiles() {
+        let mut b = BoardUtils::from_string(
+            [
+                "_ _ A _ _",
+                "_ _ N _ _",
+                "_ _ _ _ _",
+                "_ _ _ _ _",
+                "_ _ _ _ _",
+            ]
+            .join("\n"),
+            vec![Coordinate { x: 2, y: 0 }, Coordinate { x: 2, y: 4 }],
+            vec![Direction::North, Direction::South],
         )
+        .unwrap();
+        let neutral = Coordinate { x: 3, y: 1 };
+        b.set_neutral(neutral, 'Q').unwrap();
+        let mut hands = Hands::new(2, 7, TileUtils::trivial_bag());
+        let place = |position| Move::Place {
+            player: 0,
+            tile: 'A',
+            position,
+        };
+
+        // Neutral tiles block placement, can't be swapped, and don't form words
+        assert_eq!(
+            b.make_move(place(neutral), &mut hands, &short_dict()),
+            Err(GamePlayError::OccupiedPlace)
+        );
+        assert_eq!(
+            b.swap(0, [neutral, Coordinate { x: 2, y: 1 }]),
+            Err(GamePlayError::UnownedSwap)
+        );
+        assert_eq!(b.get_words(neutral), Vec::<Vec<Coordinate>>::new());
+
+        // An invalid attacking word loses against the neutral tile
+        let mut failed = b.clone();
+        let report = failed
+            .make_move_reporting(place(Coordinate { x: 3, y: 0 }), &mut hands, &short_dict())
+            .unwrap();
+        assert_eq!(report.outcome, Outcome::DefenderWins);
+        assert_eq!(failed.get(neutral), Ok(Square::Neutral('Q')));
+
+        // A valid attacking word captures it
+        let report = b
+            .make_move_reporting(
+                place(Coordinate { x: 2, y: 2 }),
+                &mut hands,
+                &Judge::new(vec!["ANA"]),
+            )
+            .unwrap();
+        assert_eq!(report.outcome, Outcome::NoBattle);
+        let report = b
+            .make_move_reporting(
+                place(Coordinate { x: 3, y: 2 }),
+                &mut hands,
+                &Judge::new(vec!["AA"]),
+            )
+            .unwrap();
+        assert_eq!(report.outcome, Outcome::AttackerWins(vec![0]));
+        assert_eq!(report.defeated_squares, vec![neutral]);
+        assert_eq!(b.get(neutral), Ok(Square::Empty));
     }
 
     #[test]
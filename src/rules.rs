@@ -0,0 +1,35 @@
+use std::collections::BTreeSet;
+
+// Toggles for variant rule sets, which change how moves are resolved on a board
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rules {
+    pub win_skips_combat: bool, // A placement that reaches the goal wins immediately, without fighting any neighbouring tiles
+    pub alphabet: BTreeSet<char>, // The only letters that can be placed. Ordered so that serialized rules are deterministic.
+    pub capture_order: CaptureOrder,
+    pub failed_attack_returns_to_hand: bool, // When an attack fails, the tile that started it goes back to the attacker's hand rather than the bag
+    pub root_immune: bool, // Tiles on players' roots are never captured, so nobody can be knocked out of the game entirely
+}
+
+// The order in which defeated defending words are cleared when an attack beats several of them at once.
+// The final board is the same either way, but the order is reported to callers, e.g. for animating captures.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CaptureOrder {
+    #[default]
+    NearestFirst, // By the average distance of the word's squares from the attacking tile
+    FarthestFirst,
+    LongestFirst,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self {
+            win_skips_combat: false,
+            alphabet: ('A'..='Z').collect(),
+            capture_order: CaptureOrder::default(),
+            failed_attack_returns_to_hand: false,
+            root_immune: false,
+        }
+    }
+}
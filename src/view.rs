@@ -0,0 +1,46 @@
+use std::collections::HashSet;
+
+use super::board::{Board, Coordinate, Square};
+
+// What a player can make out of a square in a game played with fog of war
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SeenSquare {
+    Seen(Square),
+    Hidden, // Someone else's tile that the player hasn't scouted
+}
+
+// One player's view of the board, for sending to that player when the rest of the board is hidden from them
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GameView {
+    squares: Vec<Vec<Option<SeenSquare>>>,
+}
+
+impl GameView {
+    // Shows the player their own tiles and any squares they've scouted. Without a set of scouted squares the whole board is seen.
+    pub fn new(board: &Board, player: usize, scouted: Option<&HashSet<Coordinate>>) -> Self {
+        let squares = (0..board.height())
+            .map(|y| {
+                (0..board.width())
+                    .map(|x| {
+                        let position = Coordinate { x, y };
+                        let square = board.get(position).ok()?;
+                        Some(match (square, scouted) {
+                            (Square::Occupied(owner, _, _), Some(scouted))
+                                if owner != player && !scouted.contains(&position) =>
+                            {
+                                SeenSquare::Hidden
+                            }
+                            _ => SeenSquare::Seen(square),
+                        })
+                    })
+                    .collect()
+            })
+            .collect();
+        Self { squares }
+    }
+
+    // None for squares that aren't on the board
+    pub fn get(&self, position: Coordinate) -> Option<SeenSquare> {
+        *self.squares.get(position.y)?.get(position.x)?
+    }
+}